@@ -1,14 +1,30 @@
-use std::fmt::{self, Debug, Display};
-use std::hash::{Hash, Hasher};
-use std::path::Path;
+use std::fmt::{self, Debug, Display, Write};
+use std::hash::{Hash, Hasher, BuildHasher};
+use std::collections::hash_map::RandomState;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{SystemTime, UNIX_EPOCH};
+use std::path::{Path, PathBuf, Component};
+use std::fs;
+use std::os::unix::fs::MetadataExt;
 use std::ffi::{OsStr, CStr};
 use std::os::unix::ffi::OsStrExt;
 use std::os::unix::net;
-use std::{mem, slice};
+#[cfg(target_os="linux")]
+use std::os::linux::net::SocketAddrExt;
+#[cfg(target_os="android")]
+use std::os::android::net::SocketAddrExt;
+use std::convert::TryFrom;
+use std::{mem, slice, str};
+use std::str::FromStr;
 use std::io::{self, ErrorKind};
 
+use crate::Error;
+
 use libc::{sockaddr, sa_family_t, AF_UNIX, socklen_t, sockaddr_un, c_char};
 
+#[cfg(feature="serde")]
+use serde::{Serialize, Serializer, Deserialize, Deserializer, de};
+
 /// Offset of `.sun_path` in `sockaddr_un`.
 ///
 /// This is not always identical to `mem::size_of::<sa_family_t>()`,
@@ -29,7 +45,23 @@ const fn as_char(slice: &[u8]) -> &[c_char] {
     unsafe { &*(slice as *const[u8] as *const[c_char]) }
 }
 
-const TOO_LONG_DESC: &str = "address is too long";
+/// Removes `.` components, repeated slashes and trailing slashes.
+fn normalize_path(path: &Path) -> PathBuf {
+    path.components().filter(|&component| component != Component::CurDir ).collect()
+}
+
+/// Returns `-{pid}-{counter}-{random}`, for generating unique names.
+pub(crate) fn unique_suffix() -> String {
+    static COUNTER: AtomicUsize = AtomicUsize::new(0);
+    let count = COUNTER.fetch_add(1, Ordering::Relaxed);
+    // RandomState is seeded from the OS once per thread,
+    // and the time makes it differ between hashers too.
+    let mut hasher = RandomState::new().build_hasher();
+    if let Ok(since_epoch) = SystemTime::now().duration_since(UNIX_EPOCH) {
+        hasher.write_u128(since_epoch.as_nanos());
+    }
+    format!("-{}-{}-{:016x}", std::process::id(), count, hasher.finish())
+}
 
 /// A unix domain socket address.
 ///
@@ -51,6 +83,38 @@ const TOO_LONG_DESC: &str = "address is too long";
 /// assert!(addr.is_abstract());
 /// assert_eq!(addr.to_string(), "@abstract");
 /// ```
+///
+/// # Textual representation
+///
+/// The `Display` and `FromStr` implementations use a lossless format,
+/// so that addresses can be stored in configuration files or logs and be
+/// parsed back into the same address:
+///
+/// * The unnamed address is `unnamed`.
+/// * Abstract addresses start with `@` followed by the name.
+/// * Anything else is a path.
+///
+/// Bytes of abstract names and paths that are not printable UTF-8 are
+/// escaped: `\\`, `\0`, `\n`, `\r` and `\t` have their usual meaning,
+/// and other control characters and invalid UTF-8 become `\xHH`.
+/// A path that starts with `@` is written as `\@`, and a path that is
+/// exactly `unnamed` is written as `\x75nnamed`.
+///
+/// Unlike [`new()`](#method.new), parsing with `FromStr` interprets these
+/// escape sequences.
+///
+#[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+#[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+/// use uds::UnixSocketAddr;
+///
+/// let addr = UnixSocketAddr::from_abstract(b"tab\there\0\xff").unwrap();
+/// assert_eq!(addr.to_string(), "@tab\\there\\0\\xff");
+/// assert_eq!(addr.to_string().parse::<UnixSocketAddr>().unwrap(), addr);
+///
+/// let path = UnixSocketAddr::from_path("@not abstract").unwrap();
+/// assert_eq!(path.to_string(), "\\@not abstract");
+/// assert_eq!(path.to_string().parse::<UnixSocketAddr>().unwrap(), path);
+/// ```
 #[derive(Clone,Copy)]
 pub struct UnixSocketAddr {
     addr: sockaddr_un,
@@ -151,13 +215,143 @@ impl Debug for UnixSocketAddr {
     }
 }
 
+/// Writes bytes as UTF-8 where possible, escaping everything else.
+fn write_escaped(fmtr: &mut fmt::Formatter,  mut bytes: &[u8]) -> fmt::Result {
+    while !bytes.is_empty() {
+        let (valid, invalid_len) = match str::from_utf8(bytes) {
+            Ok(valid) => (valid, 0),
+            Err(e) => {
+                let valid = str::from_utf8(&bytes[..e.valid_up_to()]).unwrap();
+                (valid, e.error_len().unwrap_or(bytes.len()-e.valid_up_to()))
+            }
+        };
+        for c in valid.chars() {
+            match c {
+                '\\' => fmtr.write_str("\\\\")?,
+                '\0' => fmtr.write_str("\\0")?,
+                '\n' => fmtr.write_str("\\n")?,
+                '\r' => fmtr.write_str("\\r")?,
+                '\t' => fmtr.write_str("\\t")?,
+                c if c.is_control() => {
+                    for &byte in c.encode_utf8(&mut [0; 4]).as_bytes() {
+                        write!(fmtr, "\\x{:02x}", byte)?;
+                    }
+                }
+                c => fmtr.write_char(c)?,
+            }
+        }
+        let invalid = &bytes[valid.len()..valid.len()+invalid_len];
+        for &byte in invalid {
+            write!(fmtr, "\\x{:02x}", byte)?;
+        }
+        bytes = &bytes[valid.len()+invalid_len..];
+    }
+    Ok(())
+}
+
+/// Reverses `write_escaped()`.
+fn unescape(escaped: &str) -> Result<Vec<u8>, io::Error> {
+    fn hex_digit(digit: Option<u8>) -> Result<u8, io::Error> {
+        match digit.map(|digit| (digit as char).to_digit(16) ) {
+            Some(Some(value)) => Ok(value as u8),
+            _ => Err(io::Error::new(ErrorKind::InvalidInput, "invalid \\x escape in address")),
+        }
+    }
+
+    let mut unescaped = Vec::with_capacity(escaped.len());
+    let mut bytes = escaped.bytes();
+    while let Some(byte) = bytes.next() {
+        if byte != b'\\' {
+            unescaped.push(byte);
+            continue;
+        }
+        let byte = match bytes.next() {
+            Some(b'\\') => b'\\',
+            Some(b'0') => b'\0',
+            Some(b'n') => b'\n',
+            Some(b'r') => b'\r',
+            Some(b't') => b'\t',
+            Some(b'@') => b'@',
+            Some(b'x') => {
+                let high = hex_digit(bytes.next())?;
+                let low = hex_digit(bytes.next())?;
+                high << 4 | low
+            }
+            _ => return Err(io::Error::new(
+                ErrorKind::InvalidInput,
+                "invalid escape sequence in address"
+            )),
+        };
+        unescaped.push(byte);
+    }
+    Ok(unescaped)
+}
+
 impl Display for UnixSocketAddr {
     fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
         match self.into() {
             UnixSocketAddrRef::Unnamed => fmtr.write_str("unnamed"),
-            UnixSocketAddrRef::Path(path) => write!(fmtr, "{}", path.display()), // TODO check that display() doesn't print \n as-is
-            UnixSocketAddrRef::Abstract(name) => write!(fmtr, "@{}", OsStr::from_bytes(name).to_string_lossy()), // FIXME escape to sane characters
+            UnixSocketAddrRef::Path(path) => {
+                let mut path = path.as_os_str().as_bytes();
+                if path == b"unnamed" {
+                    fmtr.write_str("\\x75")?;
+                    path = &path[1..];
+                } else if path.first() == Some(&b'@') {
+                    fmtr.write_str("\\@")?;
+                    path = &path[1..];
+                }
+                write_escaped(fmtr, path)
+            }
+            UnixSocketAddrRef::Abstract(name) => {
+                fmtr.write_char('@')?;
+                write_escaped(fmtr, name)
+            }
+        }
+    }
+}
+
+impl FromStr for UnixSocketAddr {
+    type Err = io::Error;
+    /// Parses the format produced by `Display`, see the type-level documentation.
+    ///
+    /// An empty string is also accepted as the unnamed address,
+    /// and a leading NUL as an alternative to `@`, like [`new()`](#method.new).
+    fn from_str(addr: &str) -> Result<Self, io::Error> {
+        if addr.is_empty()  ||  addr == "unnamed" {
+            Ok(UnixSocketAddr::new_unspecified())
+        } else if addr.starts_with('@')  ||  addr.starts_with('\0') {
+            UnixSocketAddr::from_abstract(&unescape(&addr[1..])?)
+        } else {
+            UnixSocketAddr::from_path(OsStr::from_bytes(&unescape(addr)?))
+        }
+    }
+}
+
+/// Serializes the address as a string in the format described in the
+/// [type-level documentation](#textual-representation).
+#[cfg(feature="serde")]
+impl Serialize for UnixSocketAddr {
+    fn serialize<S: Serializer>(&self,  serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// Deserializes from a string in the format described in the
+/// [type-level documentation](#textual-representation).
+#[cfg(feature="serde")]
+impl<'de> Deserialize<'de> for UnixSocketAddr {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct AddrVisitor;
+        impl<'de> de::Visitor<'de> for AddrVisitor {
+            type Value = UnixSocketAddr;
+            fn expecting(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+                fmtr.write_str("an unix socket address string")
+            }
+            fn visit_str<E: de::Error>(self,  addr: &str) -> Result<UnixSocketAddr, E> {
+                addr.parse().map_err(E::custom)
+            }
         }
+        deserializer.deserialize_str(AddrVisitor)
     }
 }
 
@@ -269,10 +463,9 @@ impl UnixSocketAddr {
             if path.is_empty() {
                 Err(io::Error::new(ErrorKind::NotFound, "path is empty"))
             } else if path.len() > capacity {
-                let message = "path is too long for an unix socket address";
-                Err(io::Error::new(ErrorKind::InvalidInput, message))
+                Err(Error::AddressTooLong{ max: capacity }.into())
             } else if path.iter().any(|&b| b == b'\0' ) {
-                Err(io::Error::new(ErrorKind::InvalidInput, "path cannot contain nul bytes"))
+                Err(Error::NulInPath.into())
             } else {
                 addr.addr.sun_path[..path.len()].copy_from_slice(as_char(path));
                 addr.len = path_offset() + path.len() as socklen_t;
@@ -327,12 +520,9 @@ impl UnixSocketAddr {
         fn from_abstract_inner(name: &[u8]) -> Result<UnixSocketAddr, io::Error> {
             let mut addr = UnixSocketAddr::new_unspecified();
             if !UnixSocketAddr::has_abstract_addresses() {
-                Err(io::Error::new(ErrorKind::AddrNotAvailable, format!(
-                            "abstract unix domain socket addresses are not available on {}",
-                            std::env::consts::OS
-                )))
+                Err(Error::AbstractUnsupported.into())
             } else if name.len() > UnixSocketAddr::max_abstract_len() {
-                Err(io::Error::new(ErrorKind::InvalidInput, "abstract name is too long"))
+                Err(Error::AddressTooLong{ max: UnixSocketAddr::max_abstract_len() }.into())
             } else {
                 addr.addr.sun_path[1..1+name.len()].copy_from_slice(as_char(name));
                 addr.len = path_offset() + 1 + name.len() as socklen_t;
@@ -342,20 +532,42 @@ impl UnixSocketAddr {
         from_abstract_inner(name.as_ref())
     }
 
-    /// Tries to convert a `std::os::unix::net::SocketAddr` into an `UnixSocketAddr`.
+    /// Creates an abstract address that is very unlikely to be in use.
+    ///
+    /// The name consists of `prefix` followed by the process ID,
+    /// a per-process counter and some randomness.
+    /// [`autobind()`](trait.UnixDatagramExt.html#method.autobind) and similar
+    /// methods use this with a prefix of `uds` and retry if the name
+    /// does turn out to be taken.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the prefix is too long, or abstract addresses are not supported.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+    /// # use uds::UnixSocketAddr;
+    /// let a = UnixSocketAddr::unique_abstract("my test").unwrap();
+    /// let b = UnixSocketAddr::unique_abstract("my test").unwrap();
+    /// assert!(a.as_abstract().unwrap().starts_with(b"my test-"));
+    /// assert_ne!(a, b);
+    /// ```
+    pub fn unique_abstract<N: AsRef<[u8]>+?Sized>(prefix: &N) -> Result<Self, io::Error> {
+        let mut name = prefix.as_ref().to_vec();
+        name.extend_from_slice(unique_suffix().as_bytes());
+        Self::from_abstract(&name)
+    }
+
+    /// Converts a `std::os::unix::net::SocketAddr` into an `UnixSocketAddr`.
     ///
-    /// This can fail (produce `None`) on Linux and Android
-    /// if the `std` `SocketAddr` represents an abstract address,
-    /// as it provides no method for viewing abstract addresses.
-    /// (other than parsing its `Debug` output, anyway.)
+    /// Returns `None` where the `TryFrom` implementation returns an error:
+    /// for paths longer than [`max_path_len()`](#method.max_path_len),
+    /// and for kinds of addresses that `UnixSocketAddr` doesn't know about.
+    /// Use `UnixSocketAddr::try_from()` to get the reason.
     pub fn from_std(addr: net::SocketAddr) -> Option<Self> {
-        if let Some(path) = addr.as_pathname() {
-            Some(Self::from_path(path).expect("pathname addr cannot be converted"))
-        } else if addr.is_unnamed() {
-            Some(Self::new_unspecified())
-        } else {
-            None
-        }
+        Self::try_from(&addr).ok()
     }
 
     /// Returns unnamed addres for empty strings, and path addresses otherwise.
@@ -370,8 +582,7 @@ impl UnixSocketAddr {
         if path.is_empty() {
             Ok(addr)
         } else if path.len() > mem::size_of_val(&addr.addr.sun_path) {
-            let message = "path is too long for unix socket address";
-            Err(io::Error::new(ErrorKind::InvalidInput, message))
+            Err(Error::AddressTooLong{ max: mem::size_of_val(&addr.addr.sun_path) }.into())
         } else {
             addr.addr.sun_path[..path.len()].copy_from_slice(as_char(path));
             addr.len = path_offset() + path.len() as socklen_t;
@@ -491,6 +702,106 @@ impl UnixSocketAddr {
         UnixSocketAddrRef::from(self)
     }
 
+    /// Returns a copy with a canonical representation, for use as a key in
+    /// maps or for comparing addresses received from different sources.
+    ///
+    /// Unnamed addresses become identical to
+    /// [`new_unspecified()`](#method.new_unspecified), and path addresses
+    /// get a single trailing NUL.  
+    /// Paths are also normalized lexically: repeated slashes, `.` components
+    /// and trailing slashes are removed. `..` is kept, as it might go
+    /// through a symlink.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use uds::UnixSocketAddr;
+    /// let addr = UnixSocketAddr::from_path("./run//./app.sock").unwrap();
+    /// assert_eq!(addr.normalized(), UnixSocketAddr::from_path("run/app.sock").unwrap());
+    /// ```
+    pub fn normalized(&self) -> Self {
+        match self.as_ref() {
+            UnixSocketAddrRef::Unnamed => Self::new_unspecified(),
+            UnixSocketAddrRef::Abstract(name) => {
+                let mut addr = Self::new_unspecified();
+                addr.addr.sun_path[1..1+name.len()].copy_from_slice(as_char(name));
+                addr.len = path_offset() + 1 + name.len() as socklen_t;
+                addr
+            }
+            UnixSocketAddrRef::Path(path) => {
+                let mut normalized = normalize_path(path);
+                if normalized.as_os_str().is_empty() {
+                    normalized.push(".");
+                }
+                // can't become longer
+                Self::from_path(&normalized).expect("normalized path is valid")
+            }
+        }
+    }
+
+    /// Checks whether two addresses refer to the same socket,
+    /// after normalizing them.
+    ///
+    /// Relative paths are resolved against `relative_to` if it is `Some`,
+    /// which should be the directory the process that bound or connected
+    /// the socket was in. Otherwise relative paths are only equal to
+    /// relative paths.  
+    /// This doesn't access the file system, see
+    /// [`is_same_socket_file()`](#method.is_same_socket_file) for that.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::path::Path;
+    /// # use uds::UnixSocketAddr;
+    /// let absolute = UnixSocketAddr::from_path("/run/app/app.sock").unwrap();
+    /// let relative = UnixSocketAddr::from_path("./app.sock").unwrap();
+    /// assert!(absolute.is_same_endpoint(&relative, Some(Path::new("/run/app/"))));
+    /// assert!(!absolute.is_same_endpoint(&relative, None));
+    /// ```
+    pub fn is_same_endpoint(&self,  other: &Self,  relative_to: Option<&Path>) -> bool {
+        match (self.resolve(relative_to), other.resolve(relative_to)) {
+            (Some(a), Some(b)) => a == b,
+            (_, _) => self.normalized() == other.normalized(),
+        }
+    }
+
+    /// Checks whether two path addresses refer to the same socket file,
+    /// by comparing the device and inode numbers of the files.
+    ///
+    /// This detects hard links and paths through symlinks,
+    /// and relative paths are resolved against `relative_to` like in
+    /// [`is_same_endpoint()`](#method.is_same_endpoint)
+    /// (or the current directory if `None`).  
+    /// Other kinds of addresses are compared with `is_same_endpoint()`.
+    ///
+    /// # Errors
+    ///
+    /// If getting the metadata of either file fails.
+    pub fn is_same_socket_file(&self,  other: &Self,  relative_to: Option<&Path>)
+    -> Result<bool, io::Error> {
+        match (self.as_pathname(), other.as_pathname()) {
+            (Some(a), Some(b)) => {
+                let (a, b) = match relative_to {
+                    Some(dir) => (dir.join(a), dir.join(b)),
+                    None => (a.to_owned(), b.to_owned()),
+                };
+                let (a, b) = (fs::metadata(a)?, fs::metadata(b)?);
+                Ok(a.dev() == b.dev()  &&  a.ino() == b.ino())
+            }
+            (_, _) => Ok(self.is_same_endpoint(other, relative_to)),
+        }
+    }
+
+    /// Joins a path address to `dir` and normalizes it, or returns `None`.
+    fn resolve(&self,  relative_to: Option<&Path>) -> Option<PathBuf> {
+        let path = self.as_pathname()?;
+        match relative_to {
+            Some(dir) => Some(normalize_path(&dir.join(path))),
+            None => Some(normalize_path(path)),
+        }
+    }
+
     /// Creates an address from a slice of bytes to place in `sun_path`.
     ///
     /// This is a low-level but simple interface for creating addresses by
@@ -554,7 +865,7 @@ impl UnixSocketAddr {
             addr.len = path_offset() + name.len() as socklen_t;
             Ok(addr)
         } else {
-            Err(io::Error::new(ErrorKind::InvalidInput, TOO_LONG_DESC))
+            Err(Error::AddressTooLong{ max: Self::max_path_len() }.into())
         }
     }
     /// Returns a low-level but view of the address without using any libc types.
@@ -674,7 +985,7 @@ impl UnixSocketAddr {
         } else if len < path_offset() {
             Err(io::Error::new(ErrorKind::InvalidInput, "address length is too low"))
         } else if len > path_offset() + mem::size_of_val(&copy.addr.sun_path) as socklen_t {
-            Err(io::Error::new(ErrorKind::InvalidInput, TOO_LONG_DESC))
+            Err(Error::AddressTooLong{ max: mem::size_of_val(&copy.addr.sun_path) }.into())
         } else if (&*addr).sa_family != AF_UNIX as sa_family_t {
             Err(io::Error::new(ErrorKind::InvalidData, "not an unix socket address"))
         } else {
@@ -740,6 +1051,52 @@ impl UnixSocketAddr {
     }
 }
 
+impl<'a> TryFrom<&'a net::SocketAddr> for UnixSocketAddr {
+    type Error = io::Error;
+    fn try_from(addr: &'a net::SocketAddr) -> Result<Self, io::Error> {
+        if let Some(path) = addr.as_pathname() {
+            return Self::from_path(path);
+        } else if addr.is_unnamed() {
+            return Ok(Self::new_unspecified());
+        }
+        #[cfg(any(target_os="linux", target_os="android"))]
+        if let Some(name) = addr.as_abstract_name() {
+            return Self::from_abstract(name);
+        }
+        Err(io::Error::new(ErrorKind::InvalidInput, "unrecognized kind of address"))
+    }
+}
+impl TryFrom<net::SocketAddr> for UnixSocketAddr {
+    type Error = io::Error;
+    fn try_from(addr: net::SocketAddr) -> Result<Self, io::Error> {
+        Self::try_from(&addr)
+    }
+}
+
+/// Converts to a `std::os::unix::net::SocketAddr`.
+///
+/// Creating an unnamed `std` address requires creating (and closing) a socket,
+/// as `std` has no constructor for it.
+impl<'a> TryFrom<&'a UnixSocketAddr> for net::SocketAddr {
+    type Error = io::Error;
+    fn try_from(addr: &'a UnixSocketAddr) -> Result<Self, io::Error> {
+        match addr.as_ref() {
+            UnixSocketAddrRef::Path(path) => net::SocketAddr::from_pathname(path),
+            #[cfg(any(target_os="linux", target_os="android"))]
+            UnixSocketAddrRef::Abstract(name) => net::SocketAddr::from_abstract_name(name),
+            #[cfg(not(any(target_os="linux", target_os="android")))]
+            UnixSocketAddrRef::Abstract(_) => Err(Error::AbstractUnsupported.into()),
+            UnixSocketAddrRef::Unnamed => net::UnixDatagram::unbound()?.local_addr(),
+        }
+    }
+}
+impl TryFrom<UnixSocketAddr> for net::SocketAddr {
+    type Error = io::Error;
+    fn try_from(addr: UnixSocketAddr) -> Result<Self, io::Error> {
+        Self::try_from(&addr)
+    }
+}
+
 impl Default for UnixSocketAddr {
     fn default() -> Self {
         Self::new_unspecified()
@@ -30,7 +30,7 @@ use libc::SCM_CREDENTIALS;
 use libc::MSG_CMSG_CLOEXEC;
 
 use crate::helpers::*;
-use crate::UnixSocketAddr;
+use crate::{Error, UnixSocketAddr};
 use crate::credentials::{SendCredentials, ReceivedCredentials};
 #[cfg(any(target_os="linux", target_os="android"))]
 use crate::credentials::RawReceivedCredentials;
@@ -83,23 +83,21 @@ pub fn send_ancillary(
         #[cfg(any(target_os="linux", target_os="android"))]
         let creds = creds.map(|creds| {
             let creds = creds.into_raw();
-            needed_capacity += CMSG_LEN(mem::size_of_val(&creds) as u32);
+            // SPACE instead of LEN to make room for padding before any fds
+            needed_capacity += CMSG_SPACE(mem::size_of_val(&creds) as u32);
             creds
         });
         if fds.len() > 0 {
             if fds.len() > 0xff_ff_ff {
                 // need to prevent truncation.
                 // I use a lower limit in case the macros don't handle overflow.
-                return Err(io::Error::new(ErrorKind::InvalidInput, "too many file descriptors"));
+                return Err(Error::TooManyFds.into());
             }
             #[cfg(not(any(target_os="illumos", target_os="solaris")))] {
                 needed_capacity += CMSG_LEN(mem::size_of_val::<[RawFd]>(fds) as u32);
             }
             #[cfg(any(target_os="illumos", target_os="solaris"))] {
-                return Err(io::Error::new(
-                    ErrorKind::Other,
-                    "ancillary data support is not implemented yet for Illumos or Solaris"
-                ))
+                return Err(Error::UnsupportedOnPlatform.into())
             }
         }
         // stack buffer which should be big enough for most scenarios
@@ -126,7 +124,10 @@ pub fn send_ancillary(
                         header.cmsg_type = SCM_CREDENTIALS;
                         header.cmsg_len = CMSG_LEN(mem::size_of_val(&creds) as u32) as ControlLen;
                         *(CMSG_DATA(header) as *mut c_void as *mut _) = creds;
-                        header = &mut*CMSG_NXTHDR(&mut msg, header);
+                        if fds.len() > 0 {
+                            // returns NULL if there is no room for another header
+                            header = &mut*CMSG_NXTHDR(&mut msg, header);
+                        }
                     }
                 }
 
@@ -425,18 +426,11 @@ pub fn recv_ancillary<'ancillary_buf>(
 
         if ancillary_buf.len() > 0 {
             #[cfg(any(target_os="illumos", target_os="solaris"))] {
-                return Err(io::Error::new(
-                    ErrorKind::Other,
-                    "ancillary message support is not implemented yet on Illumos or Solaris, sorry"
-                ))
-            }
-            if ancillary_buf.as_ptr() as usize % mem::align_of::<cmsghdr>() != 0 {
-                let msg = "ancillary buffer is not properly aligned";
-                return Err(io::Error::new(ErrorKind::InvalidInput, msg));
+                return Err(Error::UnsupportedOnPlatform.into())
             }
-            if ancillary_buf.len() > ControlLen::max_value() as usize {
-                let msg = "ancillary buffer is too big";
-                return Err(io::Error::new(ErrorKind::InvalidInput, msg));
+            if ancillary_buf.as_ptr() as usize & (mem::align_of::<cmsghdr>()-1) != 0
+            ||  ancillary_buf.len() > ControlLen::MAX as usize {
+                return Err(Error::InvalidAncillaryBuffer.into());
             }
             msg.msg_control = ancillary_buf.as_mut_ptr() as *mut c_void;
             msg.msg_controllen = ancillary_buf.len() as ControlLen;
@@ -0,0 +1,4 @@
+//! Provides support for `SEQPACKET` sockets in async-io, and thereby smol and async-std.
+
+mod seqpacket;
+pub use seqpacket::*;
@@ -0,0 +1,361 @@
+use crate::{nonblocking, UnixSocketAddr, ConnCredentials, TempBound};
+use ::async_io::Async;
+use futures::{future::poll_fn, ready, Stream};
+use std::io::{self, ErrorKind, IoSlice, IoSliceMut};
+use std::net::Shutdown;
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, RawFd};
+use std::path::Path;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+/// An I/O object representing a Unix Sequenced-packet socket.
+///
+/// Unlike the tokio version, the methods take `&self`,
+/// so one task can send while another receives.
+///
+/// # Examples
+///
+#[cfg_attr(not(target_vendor="apple"), doc="```")]
+#[cfg_attr(target_vendor="apple", doc="```no_run")]
+/// use uds::async_io::UnixSeqpacketConn;
+///
+/// async_io::block_on(async {
+///     let (a, b) = UnixSeqpacketConn::pair().unwrap();
+///     a.send(b"hello").await.unwrap();
+///     let mut buf = [0; 10];
+///     assert_eq!(b.recv(&mut buf).await.unwrap(), 5);
+/// });
+/// ```
+pub struct UnixSeqpacketConn {
+    io: Async<nonblocking::UnixSeqpacketConn>,
+}
+
+impl UnixSeqpacketConn {
+    /// Connects to the socket named by path.
+    pub async fn connect<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let conn = nonblocking::UnixSeqpacketConn::connect(path)?;
+        let conn = Self::from_nonblocking(conn)?;
+
+        conn.io.writable().await?;
+        Ok(conn)
+    }
+    /// Connects to an unix seqpacket server listening at `addr`.
+    pub async fn connect_addr(addr: &UnixSocketAddr) -> io::Result<Self> {
+        let conn = nonblocking::UnixSeqpacketConn::connect_unix_addr(addr)?;
+        let conn = Self::from_nonblocking(conn)?;
+
+        conn.io.writable().await?;
+        Ok(conn)
+    }
+    /// Binds to an address before connecting to a listening seqpacet socket.
+    pub async fn connect_from_addr(from: &UnixSocketAddr,  to: &UnixSocketAddr)
+    -> io::Result<Self> {
+        let conn = nonblocking::UnixSeqpacketConn::connect_from_to_unix_addr(from, to)?;
+        let conn = Self::from_nonblocking(conn)?;
+
+        conn.io.writable().await?;
+        Ok(conn)
+    }
+    /// Binds to an unused abstract address before connecting to a listening
+    /// seqpacket socket, and returns the address it got.
+    ///
+    /// This only works on Linux and Android.
+    pub async fn connect_autobound(to: &UnixSocketAddr) -> io::Result<(Self, UnixSocketAddr)> {
+        let (conn, addr) = nonblocking::UnixSeqpacketConn::connect_autobound(to)?;
+        let conn = Self::from_nonblocking(conn)?;
+
+        conn.io.writable().await?;
+        Ok((conn, addr))
+    }
+
+    /// Creates an unnamed pair of connected sockets.
+    pub fn pair() -> Result<(UnixSeqpacketConn, UnixSeqpacketConn), io::Error> {
+        let (a, b) = nonblocking::UnixSeqpacketConn::pair()?;
+        let a = Self::from_nonblocking(a)?;
+        let b = Self::from_nonblocking(b)?;
+
+        Ok((a, b))
+    }
+
+    /// Registers an existing nonblocking socket with the async-io reactor.
+    pub fn from_nonblocking(conn: nonblocking::UnixSeqpacketConn) -> Result<Self, io::Error> {
+        Ok(Self { io: Async::new(conn)? })
+    }
+    /// Deregisters the connection and returns the underlying non-blocking type.
+    pub fn into_nonblocking(self) -> Result<nonblocking::UnixSeqpacketConn, io::Error> {
+        self.io.into_inner()
+    }
+    /// Creates an async-io-compatible socket from a raw file descriptor.
+    ///
+    /// This function is provided instead of implementing [`FromRawFd`](std::os::unix::io::FromRawFd)
+    /// because registering with the reactor might fail.
+    ///
+    /// # Safety
+    ///
+    /// The file descriptor must represent a connected seqpacket socket.
+    pub unsafe fn from_raw_fd(fd: RawFd) -> Result<Self, io::Error> {
+        Self::from_nonblocking(nonblocking::UnixSeqpacketConn::from_raw_fd(fd))
+    }
+
+    /// Shuts down the read, write, or both halves of this connection.
+    pub fn shutdown(&self,  how: Shutdown) -> Result<(), io::Error> {
+        self.io.get_ref().shutdown(how)
+    }
+
+    /// Returns the address of this side of the connection.
+    pub fn local_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        self.io.get_ref().local_unix_addr()
+    }
+    /// Returns the address of the other side of the connection.
+    pub fn peer_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        self.io.get_ref().peer_unix_addr()
+    }
+
+    /// Returns information about the process of the peer when the connection was established.
+    ///
+    /// See documentation of the returned type for details.
+    pub fn initial_peer_credentials(&self) -> Result<ConnCredentials, io::Error> {
+        self.io.get_ref().initial_peer_credentials()
+    }
+    /// Returns the SELinux security context of the process that created the other
+    /// end of this connection.
+    ///
+    /// See [`uds::UnixSeqpacketConn::initial_peer_selinux_context()`](crate::UnixSeqpacketConn::initial_peer_selinux_context)
+    /// for details.
+    pub fn initial_peer_selinux_context(&self,  buffer: &mut[u8]) -> Result<usize, io::Error> {
+        self.io.get_ref().initial_peer_selinux_context(buffer)
+    }
+
+    /// Returns the value of the `SO_ERROR` option.
+    pub fn take_error(&self) -> Result<Option<io::Error>, io::Error> {
+        self.io.get_ref().take_error()
+    }
+
+    /// Returns the number of bytes in packets waiting to be received.
+    pub fn recv_queue_len(&self) -> Result<usize, io::Error> {
+        self.io.get_ref().recv_queue_len()
+    }
+    /// Returns the number of bytes sent but not yet received by the peer.
+    ///
+    /// See [`uds::UnixSeqpacketConn::send_queue_len()`](crate::UnixSeqpacketConn::send_queue_len)
+    /// for what the value represents on different OSes.
+    pub fn send_queue_len(&self) -> Result<usize, io::Error> {
+        self.io.get_ref().send_queue_len()
+    }
+}
+
+impl UnixSeqpacketConn {
+    /// Sends a packet to the socket's peer.
+    pub async fn send(&self,  packet: &[u8]) -> io::Result<usize> {
+        self.io.write_with(|conn| conn.send(packet) ).await
+    }
+    /// Receives a packet from the socket's peer.
+    pub async fn recv(&self,  buffer: &mut[u8]) -> io::Result<usize> {
+        self.io.read_with(|conn| conn.recv(buffer) ).await
+    }
+
+    /// Sends a packet assembled from multiple byte slices.
+    pub async fn send_vectored(&self,  slices: &[IoSlice<'_>]) -> io::Result<usize> {
+        self.io.write_with(|conn| conn.send_vectored(slices) ).await
+    }
+    /// Receives a packet and places the bytes across multiple buffers.
+    pub async fn recv_vectored(&self,  buffers: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        self.io.read_with(|conn| conn.recv_vectored(buffers).map(|(received, _)| received ) ).await
+    }
+
+    /// Receives a packet without removing it from the incoming queue.
+    pub async fn peek(&self,  buffer: &mut[u8]) -> io::Result<usize> {
+        self.io.read_with(|conn| conn.peek(buffer) ).await
+    }
+    /// Reads a packet into multiple buffers without removing it from the incoming queue.
+    pub async fn peek_vectored(&self,  buffers: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        self.io.read_with(|conn| conn.peek_vectored(buffers).map(|(received, _)| received ) ).await
+    }
+
+    /// Sends a packet with associated file descriptors.
+    pub async fn send_fds(&self,  bytes: &[u8],  fds: &[RawFd]) -> io::Result<usize> {
+        self.io.write_with(|conn| conn.send_fds(bytes, fds) ).await
+    }
+    /// Receives a packet and associated file descriptors.
+    pub async fn recv_fds(&self,  byte_buffer: &mut[u8],  fd_buffer: &mut[RawFd])
+    -> io::Result<(usize, bool, usize)> {
+        self.io.read_with(|conn| conn.recv_fds(byte_buffer, fd_buffer) ).await
+    }
+}
+
+impl AsRef<nonblocking::UnixSeqpacketConn> for UnixSeqpacketConn {
+    fn as_ref(&self) -> &nonblocking::UnixSeqpacketConn {
+        self.io.get_ref()
+    }
+}
+
+impl AsRawFd for UnixSeqpacketConn {
+    fn as_raw_fd(&self) -> RawFd {
+        self.io.as_raw_fd()
+    }
+}
+
+impl AsFd for UnixSeqpacketConn {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.io.as_fd()
+    }
+}
+
+/// # Panics
+///
+/// If deregistering from the reactor fails, as the socket has then been closed.
+/// Use [`into_nonblocking()`](#method.into_nonblocking) to handle that error.
+impl IntoRawFd for UnixSeqpacketConn {
+    fn into_raw_fd(self) -> RawFd {
+        self.io.into_inner()
+            .expect("deregister socket from the async-io reactor")
+            .into_raw_fd()
+    }
+}
+
+
+
+/// An I/O object representing a Unix Sequenced-packet socket.
+///
+/// Incoming connections can be accepted with [`accept()`](#method.accept),
+/// or by using the listener as a [`Stream`](futures::Stream).
+///
+/// # Examples
+///
+#[cfg_attr(not(target_vendor="apple"), doc="```")]
+#[cfg_attr(target_vendor="apple", doc="```no_run")]
+/// use futures::StreamExt;
+/// use uds::async_io::{UnixSeqpacketConn, UnixSeqpacketListener};
+///
+/// async_io::block_on(async {
+///     let mut listener = UnixSeqpacketListener::bind_temp().unwrap();
+///     let client = UnixSeqpacketConn::connect_addr(listener.addr()).await.unwrap();
+///     let (server, _addr) = listener.next().await.unwrap().unwrap();
+///     client.send(b"hello").await.unwrap();
+///     assert_eq!(server.recv(&mut[0; 10]).await.unwrap(), 5);
+/// });
+/// ```
+pub struct UnixSeqpacketListener {
+    io: Async<nonblocking::UnixSeqpacketListener>,
+}
+
+impl UnixSeqpacketListener {
+    /// Creates a socket that listens for seqpacket connections on the specified socket file.
+    pub fn bind<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
+        Self::from_nonblocking(nonblocking::UnixSeqpacketListener::bind(path.as_ref())?)
+    }
+    /// Creates a socket that listens for seqpacket connections on the specified address.
+    pub fn bind_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error> {
+        Self::from_nonblocking(nonblocking::UnixSeqpacketListener::bind_unix_addr(addr)?)
+    }
+    /// Creates a socket that listens for seqpacket connections on an unused
+    /// abstract address, and returns the address.
+    ///
+    /// This only works on Linux and Android.
+    pub fn autobind() -> Result<(Self, UnixSocketAddr), io::Error> {
+        let (listener, addr) = nonblocking::UnixSeqpacketListener::autobind()?;
+        Ok((Self::from_nonblocking(listener)?, addr))
+    }
+    /// Creates a socket that listens for seqpacket connections on a temporary address.
+    ///
+    /// The returned `TempBound` derefs to the listener,
+    /// and removes the socket file, if there is one, after closing it.
+    pub fn bind_temp() -> Result<TempBound<Self>, io::Error> {
+        let (listener, temp) = nonblocking::UnixSeqpacketListener::bind_temp()?.into_parts();
+        Ok(TempBound::new(Self::from_nonblocking(listener)?, temp))
+    }
+
+    /// Registers an existing nonblocking listener with the async-io reactor.
+    pub fn from_nonblocking(listener: nonblocking::UnixSeqpacketListener)
+    -> Result<Self, io::Error> {
+        Ok(Self { io: Async::new(listener)? })
+    }
+    /// Deregisters the listener and returns the underlying non-blocking type.
+    pub fn into_nonblocking(self) -> Result<nonblocking::UnixSeqpacketListener, io::Error> {
+        self.io.into_inner()
+    }
+    /// Creates an async-io-compatible listener from a raw file descriptor.
+    ///
+    /// This function is provided instead of implementing [`FromRawFd`](std::os::unix::io::FromRawFd)
+    /// because registering with the reactor might fail.
+    ///
+    /// # Safety
+    ///
+    /// The file descriptor must represent a non-blocking seqpacket listener.
+    pub unsafe fn from_raw_fd(fd: RawFd) -> Result<Self, io::Error> {
+        Self::from_nonblocking(nonblocking::UnixSeqpacketListener::from_raw_fd(fd))
+    }
+
+    /// Accepts a new incoming connection to this listener.
+    pub async fn accept(&self) -> io::Result<(UnixSeqpacketConn, UnixSocketAddr)> {
+        poll_fn(|cx| self.poll_accept(cx) ).await
+    }
+
+    fn poll_accept(&self,  cx: &mut Context<'_>)
+    -> Poll<io::Result<(UnixSeqpacketConn, UnixSocketAddr)>> {
+        loop {
+            match self.io.get_ref().accept_unix_addr() {
+                Ok((conn, addr)) => {
+                    let conn = UnixSeqpacketConn::from_nonblocking(conn)?;
+                    return Poll::Ready(Ok((conn, addr)));
+                }
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                    ready!(self.io.poll_readable(cx))?;
+                }
+                Err(e) => return Poll::Ready(Err(e)),
+            }
+        }
+    }
+
+    /// Returns the address the socket is listening on.
+    pub fn local_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        self.io.get_ref().local_unix_addr()
+    }
+
+    /// Returns the value of the `SO_ERROR` option.
+    ///
+    /// This might never produce any errors for listeners. It is therefore
+    /// unlikely to be useful, but is provided for parity with
+    /// `std::unix::net::UnixListener`.
+    pub fn take_error(&self) -> Result<Option<io::Error>, io::Error> {
+        self.io.get_ref().take_error()
+    }
+}
+
+/// Accepts connections forever.
+impl Stream for UnixSeqpacketListener {
+    type Item = io::Result<(UnixSeqpacketConn, UnixSocketAddr)>;
+    fn poll_next(self: Pin<&mut Self>,  cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_accept(cx).map(Some)
+    }
+}
+
+impl AsRef<nonblocking::UnixSeqpacketListener> for UnixSeqpacketListener {
+    fn as_ref(&self) -> &nonblocking::UnixSeqpacketListener {
+        self.io.get_ref()
+    }
+}
+
+impl AsRawFd for UnixSeqpacketListener {
+    fn as_raw_fd(&self) -> RawFd {
+        self.io.as_raw_fd()
+    }
+}
+
+impl AsFd for UnixSeqpacketListener {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.io.as_fd()
+    }
+}
+
+/// # Panics
+///
+/// If deregistering from the reactor fails, as the socket has then been closed.
+/// Use [`into_nonblocking()`](#method.into_nonblocking) to handle that error.
+impl IntoRawFd for UnixSeqpacketListener {
+    fn into_raw_fd(self) -> RawFd {
+        self.io.into_inner()
+            .expect("deregister socket from the async-io reactor")
+            .into_raw_fd()
+    }
+}
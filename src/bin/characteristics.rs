@@ -2,6 +2,8 @@
 //! certain things or how they behave.
 //! (tests that explore platform behavior are moved here after failing on some
 //!  operating system.)
+//!
+//! With `--json`, prints what `uds::capabilities()` detects as a JSON object instead.
 
 use std::fs::remove_file;
 use std::os::unix::net::{UnixDatagram, UnixListener, UnixStream};
@@ -547,7 +549,39 @@ fn print_credentials() {
     }
 }
 
+fn print_json() {
+    let c = uds::capabilities();
+    let fields: &[(&str, &dyn std::fmt::Display)] = &[
+        ("seqpacket", &c.seqpacket),
+        ("seqpacket_empty_packets", &c.seqpacket_empty_packets),
+        ("abstract_addresses", &c.abstract_addresses),
+        ("max_path_len", &c.max_path_len),
+        ("peer_credentials", &c.peer_credentials),
+        ("pair_credentials", &c.pair_credentials),
+        ("peer_pid", &c.peer_pid),
+        ("peer_groups", &c.peer_groups),
+        ("peer_pidfd", &c.peer_pidfd),
+        ("scm_credentials", &c.scm_credentials),
+        ("fd_passing", &c.fd_passing),
+        ("max_fds_per_message", &c.max_fds_per_message),
+        ("fds_cloned_on_send", &c.fds_cloned_on_send),
+        ("accept_timeout", &c.accept_timeout),
+    ];
+    println!("{{");
+    println!("  \"os\": \"{}\",", std::env::consts::OS);
+    println!("  \"arch\": \"{}\",", std::env::consts::ARCH);
+    for (i, (name, value)) in fields.iter().enumerate() {
+        let separator = if i+1 == fields.len() {""} else {","};
+        println!("  \"{}\": {}{}", name, value, separator);
+    }
+    println!("}}");
+}
+
 fn main() {
+    if std::env::args().skip(1).any(|arg| arg == "--json") {
+        print_json();
+        return;
+    }
     println!("OS {}", std::env::consts::OS);
     std_bind_max_len_path();
     std_get_local_max_len_path();
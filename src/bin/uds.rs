@@ -0,0 +1,338 @@
+//! A socat-like tool for unix domain sockets, supporting abstract addresses,
+//! seqpacket sockets and passing file descriptors.
+
+extern crate uds;
+
+use std::io::{self, ErrorKind, Read, Write};
+use std::mem;
+use std::net::Shutdown;
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::net::{UnixDatagram, UnixListener, UnixStream};
+use std::process::{exit, Child, Command};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+use uds::{ChildSocket, CommandSocketExt, UnixSocketAddr};
+use uds::{UnixDatagramExt, UnixListenerExt, UnixSeqpacketConn, UnixSeqpacketListener, UnixStreamExt};
+
+const USAGE: &str = "\
+Usage: uds [OPTIONS] SOCKET [SOCKET]
+
+Relays between SOCKET and stdin/stdout, or between two SOCKETs.
+
+SOCKET is MODE-TYPE:ADDRESS, where
+  MODE is listen or connect,
+  TYPE is stream, seqpacket or dgram, and
+  ADDRESS is a path or @abstract.
+Listening stream and seqpacket sockets accept one connection,
+and print the credentials of the peer to stderr.
+Listening datagram sockets reply to the most recent sender.
+
+Options:
+  --send-fd FD          send FD with the first message (can be repeated)
+  --recv-fd-exec CMD    run CMD with `sh -c` for each message with fds,
+                        which are passed as fd 3 and up
+                        (received fds are otherwise forwarded to the other SOCKET)
+  -h, --help            print this help
+";
+
+fn fail(msg: &str) -> ! {
+    eprintln!("uds: {}", msg);
+    exit(1);
+}
+
+fn usage_error(msg: &str) -> ! {
+    eprintln!("uds: {}", msg);
+    eprint!("{}", USAGE);
+    exit(2);
+}
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+enum Type {
+    Stream,
+    Seqpacket,
+    Datagram,
+}
+
+struct SocketArg {
+    listen: bool,
+    socket_type: Type,
+    addr: UnixSocketAddr,
+}
+
+fn parse_socket(arg: &str) -> SocketArg {
+    let (kind, addr) = arg.split_once(':')
+        .unwrap_or_else(|| usage_error(&format!("{:?} is missing :ADDRESS", arg)) );
+    let (mode, socket_type) = kind.split_once('-')
+        .unwrap_or_else(|| usage_error(&format!("{:?} is not MODE-TYPE", kind)) );
+    let listen = match mode {
+        "listen" => true,
+        "connect" => false,
+        _ => usage_error(&format!("unknown mode {:?}", mode)),
+    };
+    let socket_type = match socket_type {
+        "stream" => Type::Stream,
+        "seqpacket" => Type::Seqpacket,
+        "dgram" => Type::Datagram,
+        _ => usage_error(&format!("unknown socket type {:?}", socket_type)),
+    };
+    let addr = UnixSocketAddr::new(addr)
+        .unwrap_or_else(|e| usage_error(&format!("invalid address {:?}: {}", addr, e)) );
+    SocketArg { listen, socket_type, addr }
+}
+
+/// One side of the relay.
+enum End {
+    Stdio,
+    Stream(UnixStream),
+    Seqpacket(UnixSeqpacketConn),
+    /// with the address to reply to, if listening
+    Datagram(UnixDatagram, Option<Arc<Mutex<Option<UnixSocketAddr>>>>),
+}
+
+impl End {
+    fn open(arg: &SocketArg) -> io::Result<End> {
+        match (arg.socket_type, arg.listen) {
+            (Type::Stream, false) => UnixStream::connect_to_unix_addr(&arg.addr).map(End::Stream),
+            (Type::Seqpacket, false) => UnixSeqpacketConn::connect_unix_addr(&arg.addr).map(End::Seqpacket),
+            (Type::Datagram, false) => {
+                let socket = UnixDatagram::unbound()?;
+                // so that the peer can reply, where abstract addresses are supported
+                let _ = socket.autobind();
+                socket.connect_to_unix_addr(&arg.addr)?;
+                Ok(End::Datagram(socket, None))
+            }
+            (Type::Stream, true) => {
+                let listener = UnixListener::bind_unix_addr(&arg.addr)?;
+                eprintln!("uds: listening on {}", arg.addr);
+                let (conn, addr) = listener.accept_unix_addr()?;
+                print_peer(&addr, conn.initial_peer_credentials(), |buf| {
+                    conn.initial_peer_selinux_context(buf)
+                });
+                Ok(End::Stream(conn))
+            }
+            (Type::Seqpacket, true) => {
+                let listener = UnixSeqpacketListener::bind_unix_addr(&arg.addr)?;
+                eprintln!("uds: listening on {}", arg.addr);
+                let (conn, addr) = listener.accept_unix_addr()?;
+                print_peer(&addr, conn.initial_peer_credentials(), |buf| {
+                    conn.initial_peer_selinux_context(buf)
+                });
+                Ok(End::Seqpacket(conn))
+            }
+            (Type::Datagram, true) => {
+                let socket = UnixDatagram::bind_unix_addr(&arg.addr)?;
+                eprintln!("uds: bound to {}", arg.addr);
+                Ok(End::Datagram(socket, Some(Arc::new(Mutex::new(None)))))
+            }
+        }
+    }
+
+    fn try_clone(&self) -> io::Result<End> {
+        Ok(match self {
+            End::Stdio => End::Stdio,
+            End::Stream(stream) => End::Stream(stream.try_clone()?),
+            End::Seqpacket(conn) => End::Seqpacket(conn.try_clone()?),
+            End::Datagram(socket, reply_to) => End::Datagram(socket.try_clone()?, reply_to.clone()),
+        })
+    }
+
+    /// Receives a message, returns `None` at end of file.
+    fn recv(&self,  buf: &mut[u8]) -> io::Result<Option<(usize, Vec<OwnedFd>)>> {
+        let mut fd_buf = [-1; 253];
+        let (received, fds) = match self {
+            End::Stdio => (io::stdin().read(buf)?, 0),
+            End::Stream(stream) => stream.recv_fds(buf, &mut fd_buf)?,
+            End::Seqpacket(conn) => {
+                let (received, _, fds) = conn.recv_fds(buf, &mut fd_buf)?;
+                (received, fds)
+            }
+            End::Datagram(socket, None) => socket.recv_fds(buf, &mut fd_buf)?,
+            End::Datagram(socket, Some(reply_to)) => {
+                let (received, fds, from) = socket.recv_fds_from(buf, &mut fd_buf)?;
+                *reply_to.lock().unwrap() = Some(from);
+                (received, fds)
+            }
+        };
+        let fds = fd_buf[..fds].iter().map(|&fd| unsafe { OwnedFd::from_raw_fd(fd) } ).collect::<Vec<_>>();
+        let is_datagram = matches!(self, End::Datagram(..));
+        if received == 0  &&  fds.is_empty()  &&  !is_datagram {
+            return Ok(None);
+        }
+        Ok(Some((received, fds)))
+    }
+
+    fn send(&self,  bytes: &[u8],  fds: &[RawFd]) -> io::Result<()> {
+        match self {
+            End::Stdio if fds.is_empty() => {
+                let mut stdout = io::stdout().lock();
+                stdout.write_all(bytes)?;
+                stdout.flush()
+            }
+            End::Stdio => Err(io::Error::new(ErrorKind::InvalidInput, "cannot write fds to stdout")),
+            End::Stream(stream) => {
+                let mut stream = stream;
+                let sent = stream.send_fds(bytes, fds)?;
+                stream.write_all(&bytes[sent..])
+            }
+            End::Seqpacket(conn) => conn.send_fds(bytes, fds).map(|_| () ),
+            End::Datagram(socket, None) => socket.send_fds(bytes, fds).map(|_| () ),
+            End::Datagram(socket, Some(reply_to)) => match &*reply_to.lock().unwrap() {
+                Some(addr) => socket.send_fds_to(bytes, fds, addr).map(|_| () ),
+                None => Err(io::Error::new(ErrorKind::NotConnected, "nobody to reply to yet")),
+            },
+        }
+    }
+
+    /// Signals end of file to the peer, if possible.
+    fn shutdown_write(&self) {
+        let _ = match self {
+            End::Stdio => Ok(()),
+            End::Stream(stream) => stream.shutdown(Shutdown::Write),
+            End::Seqpacket(conn) => conn.shutdown(Shutdown::Write),
+            End::Datagram(..) => Ok(()),
+        };
+    }
+}
+
+fn print_peer<F>(addr: &UnixSocketAddr,  credentials: io::Result<uds::ConnCredentials>,  selinux: F)
+where F: FnOnce(&mut[u8]) -> io::Result<usize> {
+    eprintln!("uds: accepted connection from {}", addr);
+    match credentials {
+        Ok(credentials) => eprintln!("uds: peer credentials: {:?}", credentials),
+        Err(e) => eprintln!("uds: peer credentials not available: {}", e),
+    }
+    let mut buf = [0; 1024];
+    if let Ok(len) = selinux(&mut buf) {
+        eprintln!("uds: peer SELinux context: {}", String::from_utf8_lossy(&buf[..len]));
+    }
+}
+
+type Children = Arc<Mutex<Vec<Child>>>;
+
+fn run_with_fds(cmd: &str,  fds: &[OwnedFd],  children: &Children) -> io::Result<()> {
+    let sockets = fds.iter().enumerate()
+        .map(|(i, fd)| ChildSocket::new(fd, 3 + i as RawFd) )
+        .collect::<Vec<_>>();
+    let child = Command::new("sh")
+        .arg("-c")
+        .arg(cmd)
+        .pass_sockets(&sockets)?
+        .spawn()?;
+    children.lock().unwrap().push(child);
+    Ok(())
+}
+
+/// Copies messages from `from` to `to` until end of file.
+fn relay(from: End,  to: End,  mut send_fds: Vec<OwnedFd>,  exec: Option<(Arc<String>, Children)>)
+-> io::Result<()> {
+    let mut buf = vec![0; 65536];
+    while let Some((received, fds)) = from.recv(&mut buf)? {
+        let forward = match (&exec, fds.is_empty()) {
+            (Some((cmd, children)), false) => {
+                run_with_fds(cmd, &fds, children)?;
+                Vec::new()
+            }
+            _ => fds,
+        };
+        let forward = send_fds.drain(..).chain(forward).collect::<Vec<_>>();
+        let raw = forward.iter().map(|fd| fd.as_raw_fd() ).collect::<Vec<_>>();
+        if !raw.is_empty()  &&  matches!(to, End::Stdio) {
+            eprintln!("uds: closing {} received fds (use --recv-fd-exec)", raw.len());
+            to.send(&buf[..received], &[])?;
+        } else {
+            to.send(&buf[..received], &raw)?;
+        }
+    }
+    if !send_fds.is_empty() {
+        // nothing to attach them to
+        let raw = send_fds.iter().map(|fd| fd.as_raw_fd() ).collect::<Vec<_>>();
+        match to {
+            End::Stream(_) => return Err(io::Error::new(
+                ErrorKind::UnexpectedEof,
+                "no data to attach fds to"
+            )),
+            _ => to.send(&[], &raw)?,
+        }
+    }
+    to.shutdown_write();
+    Ok(())
+}
+
+fn main() {
+    let mut sockets = Vec::new();
+    let mut send_fds = Vec::new();
+    let mut exec = None;
+    let mut args = std::env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => {
+                print!("{}", USAGE);
+                return;
+            }
+            "--send-fd" => {
+                let fd = args.next().and_then(|fd| fd.parse::<RawFd>().ok() )
+                    .filter(|&fd| fd >= 0 )
+                    .unwrap_or_else(|| usage_error("--send-fd requires a file descriptor number") );
+                // dup so that it can be closed after sending
+                let dup = unsafe { libc::fcntl(fd, libc::F_DUPFD_CLOEXEC, 0) };
+                if dup == -1 {
+                    fail(&format!("fd {}: {}", fd, io::Error::last_os_error()));
+                }
+                send_fds.push(unsafe { OwnedFd::from_raw_fd(dup) });
+            }
+            "--recv-fd-exec" => {
+                let cmd = args.next().unwrap_or_else(|| usage_error("--recv-fd-exec requires a command") );
+                exec = Some((Arc::new(cmd), Children::default()));
+            }
+            option if option.starts_with('-') => usage_error(&format!("unknown option {}", option)),
+            _ => sockets.push(parse_socket(&arg)),
+        }
+    }
+    if sockets.is_empty()  ||  sockets.len() > 2 {
+        usage_error("expected one or two sockets");
+    }
+
+    let first = End::open(&sockets[0]).unwrap_or_else(|e| fail(&e.to_string()) );
+    let second = match sockets.get(1) {
+        Some(arg) => End::open(arg).unwrap_or_else(|e| fail(&e.to_string()) ),
+        None => End::Stdio,
+    };
+    let clones = first.try_clone().and_then(|first| Ok((first, second.try_clone()?)) );
+    let (first_clone, second_clone) = clones.unwrap_or_else(|e| fail(&e.to_string()) );
+
+    // fds are sent to the first socket with the first message to it
+    let (done_tx, done_rx) = mpsc::channel();
+    let wait_for_replies = matches!(second, End::Stdio)  &&  !matches!(first, End::Datagram(..));
+    let children = exec.as_ref().map(|(_, children)| children.clone() );
+    let forward_exec = exec.clone();
+    let forward_done = done_tx.clone();
+    thread::spawn(move || {
+        let result = relay(second, first_clone, send_fds, forward_exec);
+        let _ = forward_done.send((false, result));
+    });
+    thread::spawn(move || {
+        let result = relay(first, second_clone, Vec::new(), exec);
+        let _ = done_tx.send((true, result));
+    });
+
+    let (from_first, result) = done_rx.recv().unwrap();
+    if let Err(e) = result {
+        fail(&e.to_string());
+    }
+    // keep receiving replies after stdin is closed
+    if !from_first  &&  wait_for_replies {
+        if let Ok((_, Err(e))) = done_rx.recv() {
+            fail(&e.to_string());
+        }
+    }
+    // don't leave commands started by --recv-fd-exec running in the background
+    if let Some(children) = children {
+        let children = mem::take(&mut *children.lock().unwrap());
+        for mut child in children {
+            if let Err(e) = child.wait() {
+                eprintln!("uds: waiting for command failed: {}", e);
+            }
+        }
+    }
+}
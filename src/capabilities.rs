@@ -0,0 +1,301 @@
+//! Probing what the OS supports at runtime.
+
+use std::io::{ErrorKind, IoSliceMut};
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::net::{UnixDatagram, UnixListener, UnixStream};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::thread;
+use std::time::Duration;
+
+use crate::ancillary::*;
+use crate::helpers::*;
+use crate::{nonblocking, UnixDatagramExt, UnixListenerExt, UnixSocketAddr, UnixStreamExt};
+
+/// How many file descriptors to try sending in one message at most.
+const MAX_FDS_PROBED: usize = 512;
+
+/// What the OS supports, as detected by [`capabilities()`](fn.capabilities.html).
+///
+/// More fields might be added in the future.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+#[non_exhaustive]
+pub struct Capabilities {
+    /// Whether `SOCK_SEQPACKET` sockets can be created.
+    pub seqpacket: bool,
+    /// Whether empty seqpacket packets are received as such, instead of
+    /// being dropped.
+    pub seqpacket_empty_packets: bool,
+    /// Whether abstract addresses can be bound to.
+    pub abstract_addresses: bool,
+    /// The longest path that fits in an address.
+    pub max_path_len: usize,
+    /// Whether the credentials of the peer of a connected socket are available.
+    pub peer_credentials: bool,
+    /// Whether the credentials of the peer of a socket pair are available.
+    pub pair_credentials: bool,
+    /// Whether peer credentials include the process ID.
+    pub peer_pid: bool,
+    /// Whether peer credentials include group memberships.
+    pub peer_groups: bool,
+    /// Whether a pidfd for the peer can be obtained with `SO_PEERPIDFD`.
+    pub peer_pidfd: bool,
+    /// Whether credentials can be sent as ancillary data with `SCM_CREDENTIALS`,
+    /// together with file descriptors.
+    pub scm_credentials: bool,
+    /// Whether file descriptors can be sent.
+    pub fd_passing: bool,
+    /// The most file descriptors that could be sent in one message,
+    /// probed up to 512.
+    pub max_fds_per_message: usize,
+    /// Whether file descriptors are cloned when sent, so that they can be
+    /// closed before the peer has received them.
+    pub fds_cloned_on_send: bool,
+    /// Whether `accept()` on a blocking listener times out after the
+    /// receive timeout (`SO_RCVTIMEO`).
+    pub accept_timeout: bool,
+}
+
+/// Detects what the OS supports, by creating sockets and trying.
+///
+/// This takes a few milliseconds, and up to a fifth of a second on OSes
+/// where `accept()` ignores timeouts, so store the result instead of
+/// calling it repeatedly.
+///
+/// # Examples
+///
+/// ```
+/// let capabilities = uds::capabilities();
+/// if !capabilities.seqpacket {
+///     println!("seqpacket sockets will be emulated");
+/// }
+/// ```
+pub fn capabilities() -> Capabilities {
+    let (peer_credentials, peer_pid, peer_groups) = probe_peer_credentials();
+    let max_fds_per_message = probe_max_fds();
+    Capabilities {
+        seqpacket: nonblocking::UnixSeqpacketConn::pair().is_ok(),
+        seqpacket_empty_packets: probe_seqpacket_empty_packets(),
+        abstract_addresses: UnixDatagram::unbound().and_then(|socket| socket.autobind() ).is_ok(),
+        max_path_len: UnixSocketAddr::max_path_len(),
+        peer_credentials,
+        pair_credentials: UnixStream::pair()
+            .and_then(|(a, _b)| a.initial_peer_credentials() )
+            .is_ok(),
+        peer_pid,
+        peer_groups,
+        peer_pidfd: probe_peer_pidfd(),
+        scm_credentials: probe_scm_credentials(),
+        fd_passing: max_fds_per_message > 0,
+        max_fds_per_message,
+        fds_cloned_on_send: probe_fds_cloned_on_send(),
+        accept_timeout: probe_accept_timeout(),
+    }
+}
+
+fn probe_peer_credentials() -> (bool, bool, bool) {
+    let credentials = UnixListener::bind_temp().and_then(|listener| {
+        UnixStream::connect_to_unix_addr(listener.addr())?.initial_peer_credentials()
+    });
+    match credentials {
+        Ok(credentials) => (true, credentials.pid().is_some(), !credentials.groups().is_empty()),
+        Err(_) => (false, false, false),
+    }
+}
+
+fn probe_seqpacket_empty_packets() -> bool {
+    let (a, b) = match nonblocking::UnixSeqpacketConn::pair() {
+        Ok(pair) => pair,
+        Err(_) => return false,
+    };
+    // some OSes succeed with an empty buffer even when there is nothing to receive
+    if b.recv(&mut[]).is_ok()  ||  a.send(&[]).is_err() {
+        return false;
+    }
+    matches!(b.recv(&mut[0; 8]), Ok(0))
+}
+
+/// Sends `fd` `count` times, and receives them.
+fn try_send_fds(a: &UnixDatagram,  b: &UnixDatagram,  fd: RawFd,  count: usize) -> bool {
+    if a.send_fds(b"fds", &vec![fd; count]).is_err() {
+        return false;
+    }
+    let mut fd_buf = vec![-1; count];
+    match recv_fds(b.as_raw_fd(), None, &mut[IoSliceMut::new(&mut[0; 8])], &mut fd_buf) {
+        Ok((_, _, received)) => {
+            for &fd in &fd_buf[..received] {
+                drop(unsafe { OwnedFd::from_raw_fd(fd) });
+            }
+            received == count
+        }
+        Err(_) => false,
+    }
+}
+
+fn probe_max_fds() -> usize {
+    let (a, b) = match UnixDatagram::pair() {
+        Ok(pair) => pair,
+        Err(_) => return 0,
+    };
+    if set_nonblocking(b.as_raw_fd(), true).is_err()
+    ||  !try_send_fds(&a, &b, a.as_raw_fd(), 1) {
+        return 0;
+    }
+    // binary search for the highest count that works
+    let (mut works, mut fails) = (1, MAX_FDS_PROBED+1);
+    while fails - works > 1 {
+        let middle = works + (fails - works) / 2;
+        if try_send_fds(&a, &b, a.as_raw_fd(), middle) {
+            works = middle;
+        } else {
+            fails = middle;
+        }
+    }
+    works
+}
+
+fn probe_fds_cloned_on_send() -> bool {
+    let (a, b) = match UnixDatagram::pair() {
+        Ok(pair) => pair,
+        Err(_) => return false,
+    };
+    let to_send = match UnixDatagram::unbound() {
+        Ok(socket) => socket,
+        Err(_) => return false,
+    };
+    if set_nonblocking(b.as_raw_fd(), true).is_err()
+    ||  a.send_fds(b"fd", &[to_send.as_raw_fd()]).is_err() {
+        return false;
+    }
+    drop(to_send);
+    let mut fd_buf = [-1; 1];
+    match b.recv_fds(&mut[0; 8], &mut fd_buf) {
+        Ok((_, 1)) => {
+            let received = unsafe { OwnedFd::from_raw_fd(fd_buf[0]) };
+            socket_type(received.as_raw_fd()).is_ok()
+        }
+        _ => false,
+    }
+}
+
+fn probe_accept_timeout() -> bool {
+    let listener = match UnixListener::bind_temp() {
+        Ok(listener) => listener,
+        Err(_) => return false,
+    };
+    let timeout = Some(Duration::from_millis(10));
+    if set_timeout(listener.as_raw_fd(), TimeoutDirection::READ, timeout).is_err() {
+        return false;
+    }
+    // connect after a while, so that accept() returns even if it ignores the timeout
+    let addr = *listener.addr();
+    let (accepted, wait) = mpsc::channel::<()>();
+    let connector = thread::Builder::new().spawn(move || {
+        if let Err(RecvTimeoutError::Timeout) = wait.recv_timeout(Duration::from_millis(200)) {
+            let _ = UnixStream::connect_to_unix_addr(&addr);
+        }
+    });
+    let connector = match connector {
+        Ok(connector) => connector,
+        Err(_) => return false,
+    };
+    let timed_out = match listener.accept() {
+        Err(ref e) => e.kind() == ErrorKind::WouldBlock  ||  e.kind() == ErrorKind::TimedOut,
+        Ok(_) => false,
+    };
+    let _ = accepted.send(());
+    let _ = connector.join();
+    timed_out
+}
+
+#[cfg(any(target_os="linux", target_os="android"))]
+fn probe_peer_pidfd() -> bool {
+    #[cfg(any(target_arch="sparc", target_arch="sparc64"))]
+    const SO_PEERPIDFD: libc::c_int = 0x56;
+    #[cfg(not(any(target_arch="sparc", target_arch="sparc64")))]
+    const SO_PEERPIDFD: libc::c_int = 77;
+
+    let (a, _b) = match UnixStream::pair() {
+        Ok(pair) => pair,
+        Err(_) => return false,
+    };
+    let mut pidfd: libc::c_int = -1;
+    let mut len = std::mem::size_of_val(&pidfd) as libc::socklen_t;
+    let status = unsafe {
+        libc::getsockopt(
+            a.as_raw_fd(),
+            libc::SOL_SOCKET,
+            SO_PEERPIDFD,
+            &mut pidfd as *mut libc::c_int as *mut libc::c_void,
+            &mut len
+        )
+    };
+    if status == -1  ||  pidfd < 0 {
+        return false;
+    }
+    drop(unsafe { OwnedFd::from_raw_fd(pidfd) });
+    true
+}
+
+#[cfg(not(any(target_os="linux", target_os="android")))]
+fn probe_peer_pidfd() -> bool {
+    false
+}
+
+#[cfg(any(target_os="linux", target_os="android"))]
+fn probe_scm_credentials() -> bool {
+    use std::io::IoSlice;
+    use crate::credentials::SendCredentials;
+
+    let (a, b) = match UnixDatagram::pair() {
+        Ok(pair) => pair,
+        Err(_) => return false,
+    };
+    let enable: libc::c_int = 1;
+    let status = unsafe {
+        libc::setsockopt(
+            b.as_raw_fd(),
+            libc::SOL_SOCKET,
+            libc::SO_PASSCRED,
+            &enable as *const libc::c_int as *const libc::c_void,
+            std::mem::size_of_val(&enable) as libc::socklen_t
+        )
+    };
+    if status == -1  ||  set_nonblocking(b.as_raw_fd(), true).is_err() {
+        return false;
+    }
+    // also send a file descriptor, to check that both fit in one message
+    let sent = send_ancillary(
+        a.as_raw_fd(), None, 0,
+        &[IoSlice::new(b"credentials")], &[a.as_raw_fd()], Some(SendCredentials::Effective)
+    );
+    if sent.is_err() {
+        return false;
+    }
+    let mut ancillary_buf = AncillaryBuf::with_capacity(128);
+    let received = recv_ancillary(
+        b.as_raw_fd(), None, 0,
+        &mut[IoSliceMut::new(&mut[0; 16])], &mut ancillary_buf
+    );
+    let (mut credentials, mut fds) = (false, 0);
+    match received {
+        Ok((_, ancillary)) => for item in ancillary {
+            match item {
+                AncillaryItem::Credentials(_) => credentials = true,
+                AncillaryItem::Fds(received) => {
+                    for &fd in received {
+                        drop(unsafe { OwnedFd::from_raw_fd(fd) });
+                    }
+                    fds += received.len();
+                }
+                AncillaryItem::Unsupported => {}
+            }
+        },
+        Err(_) => return false,
+    }
+    credentials  &&  fds == 1
+}
+
+#[cfg(not(any(target_os="linux", target_os="android")))]
+fn probe_scm_credentials() -> bool {
+    false
+}
@@ -0,0 +1,355 @@
+//! Typed messages with embedded file descriptors over seqpacket connections.
+//!
+//! Messages are serialized with serde (using bincode),
+//! and file descriptors inside them are sent along as ancillary data.
+//! To send a file descriptor as part of a message, wrap it in [`Fd`](struct.Fd.html).
+//! Messages that don't fit in one packet are split across several,
+//! and receiving fails with `ErrorKind::InvalidData` if a packet or its
+//! file descriptors were truncated.
+//!
+//! Requires the `channel` feature.
+//!
+//! # Examples
+//!
+#![cfg_attr(not(target_vendor="apple"), doc="```")]
+#![cfg_attr(target_vendor="apple", doc="```no_run")]
+//! use std::io::{Read, Write};
+//! use std::os::unix::io::OwnedFd;
+//! use std::os::unix::net::UnixStream;
+//! use uds::channel::{self, Fd};
+//!
+//! let (mut sender, mut receiver) = channel::pair::<(String, Fd)>().unwrap();
+//! let (mut a, b) = UnixStream::pair().unwrap();
+//! sender.send(&("a stream".to_string(), Fd::from(OwnedFd::from(b)))).unwrap();
+//!
+//! let (name, fd) = receiver.recv().unwrap();
+//! assert_eq!(name, "a stream");
+//! let mut b = UnixStream::from(fd.into_inner());
+//! b.write_all(b"hi").unwrap();
+//! let mut buf = [0; 2];
+//! a.read_exact(&mut buf).unwrap();
+//! assert_eq!(&buf, b"hi");
+//! ```
+
+use std::cell::RefCell;
+use std::fmt;
+use std::io::{self, ErrorKind};
+use std::marker::PhantomData;
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, OwnedFd, RawFd};
+
+use serde::{de, ser, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::UnixSeqpacketConn;
+
+#[cfg(feature="tokio")]
+pub mod tokio;
+
+/// Size of the packets messages are split into.
+///
+/// Small enough to be below the default maximum on FreeBSD.
+const MAX_PACKET: usize = 8*1024;
+/// The most file descriptors that can be sent with one message.
+///
+/// This is the limit on Linux.
+pub const MAX_FDS: usize = 253;
+const MAGIC: [u8; 4] = *b"udsC";
+/// magic, message length (u32) and number of file descriptors (u16)
+const HEADER_LEN: usize = MAGIC.len() + 4 + 2;
+
+thread_local! {
+    static SENDING: RefCell<Option<Vec<RawFd>>> = const { RefCell::new(None) };
+    static RECEIVING: RefCell<Option<Vec<Option<OwnedFd>>>> = const { RefCell::new(None) };
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
+
+/// A file descriptor that can be part of messages sent through a channel.
+///
+/// Serializing or deserializing this type outside of a channel fails.
+#[derive(Debug)]
+pub struct Fd(OwnedFd);
+
+impl Fd {
+    /// Returns the wrapped file descriptor.
+    pub fn into_inner(self) -> OwnedFd {
+        self.0
+    }
+}
+
+impl From<OwnedFd> for Fd {
+    fn from(fd: OwnedFd) -> Self {
+        Fd(fd)
+    }
+}
+
+impl From<Fd> for OwnedFd {
+    fn from(fd: Fd) -> Self {
+        fd.0
+    }
+}
+
+impl AsFd for Fd {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        self.0.as_fd()
+    }
+}
+
+impl AsRawFd for Fd {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+
+impl IntoRawFd for Fd {
+    fn into_raw_fd(self) -> RawFd {
+        self.0.into_raw_fd()
+    }
+}
+
+impl FromRawFd for Fd {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        Fd(OwnedFd::from_raw_fd(fd))
+    }
+}
+
+impl Serialize for Fd {
+    fn serialize<S: Serializer>(&self,  serializer: S) -> Result<S::Ok, S::Error> {
+        let index = SENDING.with(|sending| {
+            let mut sending = sending.borrow_mut();
+            let fds = sending.as_mut().ok_or_else(|| {
+                ser::Error::custom("uds::channel::Fd can only be serialized by a channel")
+            })?;
+            fds.push(self.0.as_raw_fd());
+            Ok(fds.len() - 1)
+        })?;
+        serializer.serialize_u32(index as u32)
+    }
+}
+
+impl<'de> Deserialize<'de> for Fd {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let index = u32::deserialize(deserializer)? as usize;
+        RECEIVING.with(|receiving| {
+            let mut receiving = receiving.borrow_mut();
+            let fds = receiving.as_mut().ok_or_else(|| {
+                de::Error::custom("uds::channel::Fd can only be deserialized by a channel")
+            })?;
+            match fds.get_mut(index).and_then(Option::take) {
+                Some(fd) => Ok(Fd(fd)),
+                None => Err(de::Error::custom("message refers to a missing file descriptor")),
+            }
+        })
+    }
+}
+
+/// A serialized message, ready to be sent in one or more packets.
+pub(crate) struct Outgoing {
+    bytes: Vec<u8>,
+    fds: Vec<RawFd>,
+}
+
+impl Outgoing {
+    /// Serializes `message`, which must not be dropped before it has been sent.
+    pub(crate) fn new<T: Serialize+?Sized>(message: &T) -> Result<Self, io::Error> {
+        let previous = SENDING.with(|sending| sending.replace(Some(Vec::new())) );
+        let mut bytes = vec![0; HEADER_LEN];
+        let result = bincode::serialize_into(&mut bytes, message);
+        let fds = SENDING.with(|sending| sending.replace(previous) ).unwrap_or_default();
+        result.map_err(|e| io::Error::new(ErrorKind::InvalidInput, e) )?;
+
+        if fds.len() > MAX_FDS {
+            return Err(crate::Error::TooManyFds.into());
+        }
+        let payload_len = u32::try_from(bytes.len() - HEADER_LEN)
+            .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "message is too big") )?;
+        bytes[..MAGIC.len()].copy_from_slice(&MAGIC);
+        bytes[MAGIC.len()..MAGIC.len()+4].copy_from_slice(&payload_len.to_le_bytes());
+        bytes[MAGIC.len()+4..HEADER_LEN].copy_from_slice(&(fds.len() as u16).to_le_bytes());
+        Ok(Outgoing { bytes, fds })
+    }
+
+    /// The packets to send, and the file descriptors to send with each.
+    pub(crate) fn packets(&self) -> impl Iterator<Item=(&[u8], &[RawFd])> {
+        self.bytes.chunks(MAX_PACKET).enumerate().map(move |(i, packet)| {
+            (packet, if i == 0 {&self.fds[..]} else {&[][..]})
+        })
+    }
+}
+
+/// A partially received message.
+pub(crate) struct Incoming {
+    bytes: Vec<u8>,
+    expected: usize,
+    fds: Vec<Option<OwnedFd>>,
+}
+
+impl Incoming {
+    /// Creates an empty receive buffer for a packet,
+    /// and a buffer for file descriptors.
+    pub(crate) fn buffers() -> (Vec<u8>, Vec<RawFd>) {
+        (vec![0; MAX_PACKET], vec![-1; MAX_FDS])
+    }
+
+    /// Starts a message from its first packet, taking ownership of the received fds.
+    pub(crate) fn first(packet: &[u8],  truncated: bool,  fds: &[RawFd])
+    -> Result<Self, io::Error> {
+        let fds = fds.iter().map(|&fd| Some(unsafe { OwnedFd::from_raw_fd(fd) }) ).collect::<Vec<_>>();
+        if packet.is_empty() {
+            return Err(io::Error::new(ErrorKind::UnexpectedEof, "channel closed"));
+        }
+        if truncated {
+            return Err(invalid_data("channel packet was truncated"));
+        }
+        if packet.len() < HEADER_LEN || packet[..MAGIC.len()] != MAGIC {
+            return Err(invalid_data("not a channel message"));
+        }
+        let mut len_bytes = [0; 4];
+        len_bytes.copy_from_slice(&packet[MAGIC.len()..MAGIC.len()+4]);
+        let expected = u32::from_le_bytes(len_bytes) as usize;
+        let num_fds = u16::from_le_bytes([packet[MAGIC.len()+4], packet[MAGIC.len()+5]]);
+        if fds.len() != num_fds as usize {
+            return Err(crate::Error::AncillaryTruncated.into());
+        }
+        let mut incoming = Incoming { bytes: Vec::new(), expected, fds };
+        incoming.push(&packet[HEADER_LEN..])?;
+        Ok(incoming)
+    }
+
+    /// Adds a continuation packet, closing any unexpected fds.
+    pub(crate) fn more(&mut self,  packet: &[u8],  truncated: bool,  fds: &[RawFd])
+    -> Result<(), io::Error> {
+        for &fd in fds {
+            drop(unsafe { OwnedFd::from_raw_fd(fd) });
+        }
+        if packet.is_empty() {
+            return Err(io::Error::new(ErrorKind::UnexpectedEof, "channel closed in the middle of a message"));
+        }
+        if truncated {
+            return Err(invalid_data("channel packet was truncated"));
+        }
+        if !fds.is_empty() {
+            return Err(invalid_data("file descriptors sent in the middle of a message"));
+        }
+        self.push(packet)
+    }
+
+    fn push(&mut self,  payload: &[u8]) -> Result<(), io::Error> {
+        if payload.len() > self.expected - self.bytes.len() {
+            return Err(invalid_data("channel message is longer than announced"));
+        }
+        self.bytes.extend_from_slice(payload);
+        Ok(())
+    }
+
+    pub(crate) fn is_complete(&self) -> bool {
+        self.bytes.len() == self.expected
+    }
+
+    /// Deserializes the complete message.
+    ///
+    /// File descriptors not referred to by the message are closed.
+    pub(crate) fn decode<T: for<'de> Deserialize<'de>>(self) -> Result<T, io::Error> {
+        let previous = RECEIVING.with(|receiving| receiving.replace(Some(self.fds)) );
+        let result = bincode::deserialize(&self.bytes);
+        RECEIVING.with(|receiving| receiving.replace(previous) );
+        result.map_err(|e| io::Error::new(ErrorKind::InvalidData, e) )
+    }
+}
+
+/// Creates a connected sender and receiver.
+pub fn pair<T>() -> Result<(Sender<T>, Receiver<T>), io::Error> {
+    let (a, b) = UnixSeqpacketConn::pair()?;
+    Ok((Sender::new(a), Receiver::new(b)))
+}
+
+/// Sends messages of type `T` over a seqpacket connection.
+pub struct Sender<T: ?Sized> {
+    conn: UnixSeqpacketConn,
+    /// set when a message was only partially sent
+    poisoned: bool,
+    _message: PhantomData<fn(&T)>,
+}
+
+impl<T: ?Sized> fmt::Debug for Sender<T> {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_tuple("Sender").field(&self.conn).finish()
+    }
+}
+
+impl<T: Serialize+?Sized> Sender<T> {
+    /// Sends `message`, including any [`Fd`](struct.Fd.html)s in it.
+    ///
+    /// The file descriptors stay open in this process.
+    ///
+    /// If sending fails after part of a message has been sent,
+    /// the receiver can't tell where the next message starts,
+    /// so this and all later sends return an error.
+    pub fn send(&mut self,  message: &T) -> Result<(), io::Error> {
+        if self.poisoned {
+            let msg = "an earlier message was only partially sent";
+            return Err(io::Error::new(ErrorKind::BrokenPipe, msg));
+        }
+        let outgoing = Outgoing::new(message)?;
+        for (i, (packet, fds)) in outgoing.packets().enumerate() {
+            // send_fds() retries on EINTR
+            if let Err(e) = self.conn.send_fds(packet, fds) {
+                self.poisoned = i > 0;
+                return Err(e);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: ?Sized> Sender<T> {
+    /// Uses a connection for sending messages.
+    pub fn new(conn: UnixSeqpacketConn) -> Self {
+        Sender { conn, poisoned: false, _message: PhantomData }
+    }
+    /// Returns the connection.
+    pub fn into_inner(self) -> UnixSeqpacketConn {
+        self.conn
+    }
+}
+
+/// Receives messages of type `T` over a seqpacket connection.
+pub struct Receiver<T> {
+    conn: UnixSeqpacketConn,
+    _message: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_tuple("Receiver").field(&self.conn).finish()
+    }
+}
+
+impl<T: for<'de> Deserialize<'de>> Receiver<T> {
+    /// Waits for the next message.
+    ///
+    /// Returns an error of kind `ErrorKind::UnexpectedEof` if the sender
+    /// has been closed.
+    pub fn recv(&mut self) -> Result<T, io::Error> {
+        let (mut buf, mut fd_buf) = Incoming::buffers();
+        let (len, truncated, fds) = self.conn.recv_fds(&mut buf, &mut fd_buf)?;
+        let mut incoming = Incoming::first(&buf[..len], truncated, &fd_buf[..fds])?;
+        while !incoming.is_complete() {
+            let (len, truncated, fds) = self.conn.recv_fds(&mut buf, &mut fd_buf)?;
+            incoming.more(&buf[..len], truncated, &fd_buf[..fds])?;
+        }
+        incoming.decode()
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Uses a connection for receiving messages.
+    pub fn new(conn: UnixSeqpacketConn) -> Self {
+        Receiver { conn, _message: PhantomData }
+    }
+    /// Returns the connection.
+    pub fn into_inner(self) -> UnixSeqpacketConn {
+        self.conn
+    }
+}
@@ -0,0 +1,138 @@
+//! Channels over [tokio seqpacket connections](../../tokio/struct.UnixSeqpacketConn.html).
+//!
+//! Requires both the `channel` and `tokio` features.
+
+use std::fmt;
+use std::io;
+use std::marker::PhantomData;
+
+use serde::{Deserialize, Serialize};
+
+use crate::tokio::UnixSeqpacketConn;
+use super::{Incoming, Outgoing};
+
+/// Creates a connected sender and receiver.
+pub fn pair<T>() -> Result<(Sender<T>, Receiver<T>), io::Error> {
+    let (a, b) = UnixSeqpacketConn::pair()?;
+    Ok((Sender::new(a), Receiver::new(b)))
+}
+
+/// Sends messages of type `T` over a tokio seqpacket connection.
+pub struct Sender<T: ?Sized> {
+    conn: UnixSeqpacketConn,
+    /// a partially sent message, and how many packets of it have been sent
+    unsent: Option<(Outgoing, usize)>,
+    _message: PhantomData<fn(&T)>,
+}
+
+impl<T: ?Sized> fmt::Debug for Sender<T> {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_tuple("Sender").field(&self.conn.as_ref()).finish()
+    }
+}
+
+impl<T: Serialize+?Sized> Sender<T> {
+    /// Sends `message`, including any [`Fd`](../struct.Fd.html)s in it.
+    ///
+    /// The file descriptors stay open in this process.
+    ///
+    /// This is cancel safe: If the future is dropped after part of a
+    /// message has been sent, the rest of it is sent before the next message,
+    /// or by [`flush()`](#method.flush).
+    pub async fn send(&mut self,  message: &T) -> Result<(), io::Error> {
+        self.flush().await?;
+        let outgoing = Outgoing::new(message)?;
+        // the file descriptors are sent with the first packet,
+        // so they are not needed when finishing the message later
+        if let Some((packet, fds)) = outgoing.packets().next() {
+            self.conn.send_fds(packet, fds).await?;
+        }
+        self.unsent = Some((outgoing, 1));
+        self.flush().await
+    }
+}
+
+impl<T: ?Sized> Sender<T> {
+    /// Uses a connection for sending messages.
+    pub fn new(conn: UnixSeqpacketConn) -> Self {
+        Sender { conn, unsent: None, _message: PhantomData }
+    }
+    /// Finishes sending a message whose [`send()`](#method.send) was cancelled
+    /// or failed partway.
+    ///
+    /// Does nothing if there is no such message.
+    pub async fn flush(&mut self) -> Result<(), io::Error> {
+        loop {
+            let (outgoing, sent) = match &mut self.unsent {
+                Some(unsent) => unsent,
+                None => return Ok(()),
+            };
+            let next = outgoing.packets().nth(*sent);
+            let packet = match next {
+                Some((packet, _)) => packet,
+                None => {
+                    self.unsent = None;
+                    return Ok(());
+                }
+            };
+            // keep the rest on errors, as starting a new message would desync the receiver
+            self.conn.send_fds(packet, &[]).await?;
+            *sent += 1;
+        }
+    }
+    /// Returns the connection.
+    pub fn into_inner(self) -> UnixSeqpacketConn {
+        self.conn
+    }
+}
+
+/// Receives messages of type `T` over a tokio seqpacket connection.
+pub struct Receiver<T> {
+    conn: UnixSeqpacketConn,
+    partial: Option<Incoming>,
+    _message: PhantomData<fn() -> T>,
+}
+
+impl<T> fmt::Debug for Receiver<T> {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_tuple("Receiver").field(&self.conn.as_ref()).finish()
+    }
+}
+
+impl<T: for<'de> Deserialize<'de>> Receiver<T> {
+    /// Waits for the next message.
+    ///
+    /// Returns an error of kind `ErrorKind::UnexpectedEof` if the sender
+    /// has been closed.
+    ///
+    /// This is cancel safe: The packets of a message received before the
+    /// future is dropped are kept, and the next call continues the message.
+    pub async fn recv(&mut self) -> Result<T, io::Error> {
+        let (mut buf, mut fd_buf) = Incoming::buffers();
+        loop {
+            let (len, truncated, fds) = self.conn.recv_fds(&mut buf, &mut fd_buf).await?;
+            let incoming = match self.partial.take() {
+                None => Incoming::first(&buf[..len], truncated, &fd_buf[..fds])?,
+                Some(mut incoming) => {
+                    incoming.more(&buf[..len], truncated, &fd_buf[..fds])?;
+                    incoming
+                }
+            };
+            if incoming.is_complete() {
+                return incoming.decode();
+            }
+            self.partial = Some(incoming);
+        }
+    }
+}
+
+impl<T> Receiver<T> {
+    /// Uses a connection for receiving messages.
+    pub fn new(conn: UnixSeqpacketConn) -> Self {
+        Receiver { conn, partial: None, _message: PhantomData }
+    }
+    /// Returns the connection.
+    pub fn into_inner(self) -> UnixSeqpacketConn {
+        self.conn
+    }
+}
@@ -0,0 +1,106 @@
+use std::ffi::OsStr;
+use std::io::{self, ErrorKind};
+use std::marker::PhantomData;
+use std::os::unix::io::{AsRawFd, FromRawFd, OwnedFd, RawFd};
+use std::os::unix::process::CommandExt;
+use std::process::Command;
+
+use libc::{fcntl, dup2, F_DUPFD_CLOEXEC};
+
+/// A socket to install at a specific file descriptor number in a child process.
+///
+/// Used with [`CommandSocketExt::pass_sockets()`](trait.CommandSocketExt.html#tymethod.pass_sockets).
+#[derive(Clone,Copy, Debug)]
+pub struct ChildSocket<'a> {
+    fd: RawFd,
+    child_fd: RawFd,
+    env_var: Option<&'a OsStr>,
+    _socket: PhantomData<&'a ()>,
+}
+
+impl<'a> ChildSocket<'a> {
+    /// Makes `socket` available as `child_fd` in the child.
+    ///
+    /// Any socket type works, including std's stream and datagram types
+    /// and this crate's seqpacket types.
+    pub fn new<S: AsRawFd+?Sized>(socket: &'a S,  child_fd: RawFd) -> Self {
+        ChildSocket { fd: socket.as_raw_fd(), child_fd, env_var: None, _socket: PhantomData }
+    }
+
+    /// Also sets the environment variable `var` to the fd number in the child.
+    pub fn with_env<K: AsRef<OsStr>+?Sized>(mut self,  var: &'a K) -> Self {
+        self.env_var = Some(var.as_ref());
+        self
+    }
+}
+
+/// Extension trait for installing sockets at chosen file descriptor numbers
+/// in child processes.
+///
+/// The sockets are duplicated immediately, so the originals can be closed
+/// before spawning, and the duplicates stay close-on-exec in the parent.
+/// In the child, the duplicates are moved to their target numbers with
+/// `dup2()`, which clears close-on-exec only for those.
+/// Spawning the same `Command` more than once passes the same sockets every time.
+///
+/// # Examples
+///
+#[cfg_attr(not(target_vendor="apple"), doc="```")]
+#[cfg_attr(target_vendor="apple", doc="```no_run")]
+/// use std::process::Command;
+/// use uds::{ChildSocket, CommandSocketExt, UnixSeqpacketConn};
+///
+/// let (parent, child) = UnixSeqpacketConn::pair().unwrap();
+/// let mut command = Command::new("sh");
+/// command.args(["-c", "printf hello >&$WORKER_FD"]);
+/// command.pass_sockets(&[ChildSocket::new(&child, 3).with_env("WORKER_FD")]).unwrap();
+/// drop(child);
+/// let status = command.status().unwrap();
+/// assert!(status.success());
+/// assert_eq!(parent.recv(&mut[0; 10]).unwrap(), 5);
+/// ```
+pub trait CommandSocketExt {
+    /// Installs the sockets at their chosen fd numbers when the child is spawned.
+    ///
+    /// Target numbers must be non-negative and unique.
+    /// This should only be called once per `Command`, as sockets passed
+    /// in a later call might get replaced by targets of an earlier call.
+    fn pass_sockets(&mut self,  sockets: &[ChildSocket<'_>]) -> Result<&mut Self, io::Error>;
+}
+
+impl CommandSocketExt for Command {
+    fn pass_sockets(&mut self,  sockets: &[ChildSocket<'_>]) -> Result<&mut Self, io::Error> {
+        for (i, socket) in sockets.iter().enumerate() {
+            if socket.child_fd < 0 {
+                return Err(io::Error::new(ErrorKind::InvalidInput, "child fd is negative"));
+            }
+            if sockets[..i].iter().any(|other| other.child_fd == socket.child_fd ) {
+                return Err(io::Error::new(ErrorKind::InvalidInput, "child fd is used twice"));
+            }
+        }
+
+        // duplicate above all target numbers so that no dup2() replaces
+        // a socket that hasn't been moved yet
+        let above = sockets.iter().map(|socket| socket.child_fd + 1 ).max().unwrap_or(0);
+        let mut mappings = Vec::with_capacity(sockets.len());
+        for socket in sockets {
+            let dup = cvt!(unsafe { fcntl(socket.fd, F_DUPFD_CLOEXEC, above) })?;
+            mappings.push((unsafe { OwnedFd::from_raw_fd(dup) }, socket.child_fd));
+        }
+
+        for socket in sockets {
+            if let Some(var) = socket.env_var {
+                self.env(var, socket.child_fd.to_string());
+            }
+        }
+        let pre_exec = move || {
+            for (dup, child_fd) in &mappings {
+                cvt_r!(unsafe { dup2(dup.as_raw_fd(), *child_fd) })?;
+            }
+            Ok(())
+        };
+        // dup2() is async-signal-safe, and nothing is allocated
+        unsafe { self.pre_exec(pre_exec) };
+        Ok(self)
+    }
+}
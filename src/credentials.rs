@@ -33,6 +33,8 @@ use libc::{sockpeercred, SOL_SOCKET, SO_PEERCRED};
 use libc::{unpcbid, LOCAL_PEEREID};
 #[cfg(any(target_os="illumos", target_os="solaris"))]
 use libc::{getpeerucred, ucred_free, ucred_t};
+#[cfg(feature="serde")]
+use serde::{Serialize, Serializer, ser::SerializeStruct};
 #[cfg(any(target_os="illumos", target_os="solaris"))]
 use libc::{ucred_geteuid, ucred_getegid, ucred_getpid, ucred_getgroups, uid_t, gid_t, pid_t};
 
@@ -79,7 +81,7 @@ pub fn selinux_context(fd: RawFd,  buffer: &mut[u8]) -> Result<usize, io::Error>
 
 #[cfg(not(any(target_os="linux", target_os="android")))]
 pub fn selinux_context(_fd: RawFd,  _buffer: &mut[u8]) -> Result<usize, io::Error> {
-    Err(io::Error::new(Other, "not available"))
+    Err(crate::Error::UnsupportedOnPlatform.into())
 }
 
 
@@ -189,6 +191,29 @@ impl fmt::Debug for ConnCredentials {
     }
 }
 
+/// Serializes the same fields as the `Debug` impl,
+/// which depend on what the OS provides.
+#[cfg(feature="serde")]
+impl Serialize for ConnCredentials {
+    fn serialize<S: Serializer>(&self,  serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ConnCredentials::LinuxLike{ pid, euid, egid } => {
+                let mut repr = serializer.serialize_struct("ConnCredentials", 3)?;
+                repr.serialize_field("pid", pid)?;
+                repr.serialize_field("euid", euid)?;
+                repr.serialize_field("egid", egid)?;
+                repr.end()
+            }
+            ConnCredentials::MacOsLike{ euid, .. } => {
+                let mut repr = serializer.serialize_struct("ConnCredentials", 2)?;
+                repr.serialize_field("euid", euid)?;
+                repr.serialize_field("groups", self.groups())?;
+                repr.end()
+            }
+        }
+    }
+}
+
 
 #[cfg(any(target_os="linux", target_os="android"))]
 pub fn peer_credentials(conn: RawFd) -> Result<ConnCredentials, io::Error> {
@@ -351,7 +376,7 @@ pub fn peer_credentials(conn: RawFd) -> Result<ConnCredentials, io::Error> {
     target_os="illumos", target_os="solaris",
 )))]
 pub fn peer_credentials(_: RawFd) -> Result<ConnCredentials, io::Error> {
-    Err(io::Error::new(Other, "Not available"))
+    Err(crate::Error::UnsupportedOnPlatform.into())
 }
 
 
@@ -408,6 +433,43 @@ pub struct ReceivedCredentials {
     groups: [u32; 5],
 }
 
+/// Serializes the fields provided by the OS, which are named like in the source.
+#[cfg(feature="serde")]
+impl Serialize for ReceivedCredentials {
+    fn serialize<S: Serializer>(&self,  serializer: S) -> Result<S::Ok, S::Error> {
+        #[cfg(any(target_os="linux", target_os="android"))] {
+            let mut repr = serializer.serialize_struct("ReceivedCredentials", 3)?;
+            repr.serialize_field("pid", &self.pid)?;
+            repr.serialize_field("uid", &self.uid)?;
+            repr.serialize_field("gid", &self.gid)?;
+            repr.end()
+        }
+        #[cfg(any(
+            target_os="freebsd", target_os="netbsd", target_os="dragonfly",
+            target_os="illumos", target_os="solaris", target_os="macos",
+        ))] {
+            // DragonFly BSD has pid but not effective_gid
+            let mut repr = serializer.serialize_struct("ReceivedCredentials", 5)?;
+            #[cfg(target_os="dragonfly")]
+            repr.serialize_field("pid", &self.pid)?;
+            repr.serialize_field("real_uid", &self.real_uid)?;
+            repr.serialize_field("effective_uid", &self.effective_uid)?;
+            repr.serialize_field("real_gid", &self.real_gid)?;
+            #[cfg(not(target_os="dragonfly"))]
+            repr.serialize_field("effective_gid", &self.effective_gid)?;
+            repr.serialize_field("groups", &self.groups[..])?;
+            repr.end()
+        }
+        #[cfg(not(any(
+            target_os="linux", target_os="android",
+            target_os="freebsd", target_os="netbsd", target_os="dragonfly",
+            target_os="illumos", target_os="solaris", target_os="macos",
+        )))] {
+            serializer.serialize_struct("ReceivedCredentials", 0)?.end()
+        }
+    }
+}
+
 #[allow(unused)] // TODO
 impl ReceivedCredentials {
     #[cfg(any(target_os="linux", target_os="android"))]
@@ -0,0 +1,353 @@
+//! Inspect unix domain sockets through the Linux `sock_diag` netlink interface.
+//!
+//! This is the interface used by `ss -x`, and can tell what is on the other
+//! end of a connection by looking up the peer's inode,
+//! even for sockets not owned by the current process.
+//! Only available on Linux and Android, and the kernel must have been built
+//! with `CONFIG_UNIX_DIAG` for queries to succeed.
+//!
+//! # Examples
+//!
+//! Find the listener a connection was accepted from:
+//!
+//! ```
+//! use uds::{diag, UnixSeqpacketListener, UnixSeqpacketConn, UnixSocketAddr};
+//!
+//! let addr = UnixSocketAddr::new("@diag example").unwrap();
+//! let listener = UnixSeqpacketListener::bind_unix_addr(&addr).unwrap();
+//! let client = UnixSeqpacketConn::connect_unix_addr(&addr).unwrap();
+//! let (server, _) = listener.accept_unix_addr().unwrap();
+//!
+//! # if diag::socket_info(&client).is_err() { return } // CONFIG_UNIX_DIAG disabled
+//! let peer = diag::peer_info(&client).unwrap();
+//! assert_eq!(peer.inode, diag::socket_info(&server).unwrap().inode);
+//! assert_eq!(peer.addr, addr);
+//! assert_eq!(peer.state, diag::SocketState::Connected);
+//! ```
+
+use std::io::{self, ErrorKind};
+use std::mem;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::ptr;
+
+use libc::{c_int, c_void, AF_NETLINK, AF_UNIX, SOCK_DGRAM, SOCK_CLOEXEC};
+use libc::{socket, send, recv, close, fstat};
+
+use crate::addr::UnixSocketAddr;
+
+const NETLINK_SOCK_DIAG: c_int = 4;
+const SOCK_DIAG_BY_FAMILY: u16 = 20;
+const NLMSG_ERROR: u16 = 2;
+const NLMSG_DONE: u16 = 3;
+const NLM_F_REQUEST: u16 = 0x001;
+const NLM_F_DUMP: u16 = 0x300;
+
+const UDIAG_SHOW_NAME: u32 = 0x01;
+const UDIAG_SHOW_VFS: u32 = 0x02;
+const UDIAG_SHOW_PEER: u32 = 0x04;
+const UDIAG_SHOW_RQLEN: u32 = 0x10;
+const UDIAG_SHOW_UID: u32 = 0x40;
+
+const UNIX_DIAG_NAME: u16 = 0;
+const UNIX_DIAG_VFS: u16 = 1;
+const UNIX_DIAG_PEER: u16 = 2;
+const UNIX_DIAG_RQLEN: u16 = 4;
+const UNIX_DIAG_UID: u16 = 7;
+
+// the socket states are borrowed from TCP
+const TCP_ESTABLISHED: u8 = 1;
+const TCP_CLOSE: u8 = 7;
+const TCP_LISTEN: u8 = 10;
+
+/// Not in libc for all Linux targets.
+#[repr(C)]
+#[derive(Clone,Copy)]
+struct NlMsgHdr {
+    len: u32,
+    msg_type: u16,
+    flags: u16,
+    seq: u32,
+    pid: u32,
+}
+
+/// `struct unix_diag_req` from `linux/unix_diag.h`
+#[repr(C)]
+#[derive(Clone,Copy)]
+struct UnixDiagReq {
+    family: u8,
+    protocol: u8,
+    pad: u16,
+    states: u32,
+    inode: u32,
+    show: u32,
+    cookie: [u32; 2],
+}
+
+/// `struct unix_diag_msg` from `linux/unix_diag.h`
+#[repr(C)]
+#[derive(Clone,Copy)]
+struct UnixDiagMsg {
+    family: u8,
+    socket_type: u8,
+    state: u8,
+    pad: u8,
+    inode: u32,
+    cookie: [u32; 2],
+}
+
+#[repr(C)]
+struct Request {
+    header: NlMsgHdr,
+    body: UnixDiagReq,
+}
+
+/// Reads a possibly unaligned `T` from the start of `bytes`.
+fn read_struct<T: Copy>(bytes: &[u8]) -> Option<T> {
+    if bytes.len() < mem::size_of::<T>() {
+        return None;
+    }
+    Some(unsafe { ptr::read_unaligned(bytes.as_ptr() as *const T) })
+}
+
+/// Netlink messages and attributes are padded to multiples of four bytes.
+fn align(len: usize) -> usize {
+    (len + 3) & !3
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
+
+
+
+/// What a socket is doing, as far as the kernel is concerned.
+#[derive(Clone,Copy, PartialEq,Eq, Debug)]
+pub enum SocketState {
+    /// A socket that isn't listening or connected, such as an unconnected
+    /// datagram socket or a stream socket whose peer has been closed.
+    Unconnected,
+    /// A connected stream or seqpacket socket, or a connected datagram socket.
+    Connected,
+    /// A stream or seqpacket socket listening for connections.
+    Listening,
+    /// A state not known to this library, as the raw value from the kernel.
+    Other(u8),
+}
+
+impl SocketState {
+    fn from_raw(state: u8) -> Self {
+        match state {
+            TCP_CLOSE => SocketState::Unconnected,
+            TCP_ESTABLISHED => SocketState::Connected,
+            TCP_LISTEN => SocketState::Listening,
+            other => SocketState::Other(other),
+        }
+    }
+}
+
+/// Information about an unix domain socket as reported by the kernel.
+///
+/// Fields that are `None` weren't reported for the socket,
+/// either because they don't apply to it or because the kernel is too old.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct UnixSocketInfo {
+    /// Identifies the socket, and is what `peer_inode` refers to.
+    ///
+    /// This is the same number as `st_ino` from `fstat()`ing the socket.
+    pub inode: u32,
+    /// `SOCK_STREAM`, `SOCK_DGRAM` or `SOCK_SEQPACKET`.
+    pub socket_type: c_int,
+    /// Whether the socket is listening, connected or neither.
+    pub state: SocketState,
+    /// The address the socket is bound to, or an unnamed address.
+    pub addr: UnixSocketAddr,
+    /// The inode of the socket on the other end of the connection.
+    pub peer_inode: Option<u32>,
+    /// Bytes waiting to be received, or for listeners,
+    /// the number of connections waiting to be accepted.
+    pub recv_queue_len: Option<u32>,
+    /// Bytes sent but not yet received by the peer, or for listeners,
+    /// the maximum number of connections waiting to be accepted.
+    pub send_queue_len: Option<u32>,
+    /// The user ID of the process that created the socket. (Linux 5.3+)
+    pub uid: Option<u32>,
+    /// The inode of the socket file if bound to a path.
+    pub file_inode: Option<u32>,
+    /// The device containing the socket file if bound to a path.
+    pub file_device: Option<u32>,
+}
+
+impl UnixSocketInfo {
+    fn parse(payload: &[u8]) -> Result<Self, io::Error> {
+        let msg = read_struct::<UnixDiagMsg>(payload)
+            .ok_or_else(|| invalid_data("truncated unix_diag_msg") )?;
+        if msg.family != AF_UNIX as u8 {
+            return Err(invalid_data("sock_diag response is not for an unix socket"));
+        }
+        let mut info = UnixSocketInfo {
+            inode: msg.inode,
+            socket_type: msg.socket_type as c_int,
+            state: SocketState::from_raw(msg.state),
+            addr: UnixSocketAddr::new_unspecified(),
+            peer_inode: None,
+            recv_queue_len: None,
+            send_queue_len: None,
+            uid: None,
+            file_inode: None,
+            file_device: None,
+        };
+
+        let mut attrs = &payload[align(mem::size_of::<UnixDiagMsg>()).min(payload.len())..];
+        while attrs.len() >= 4 {
+            let attr_len = u16::from_ne_bytes([attrs[0], attrs[1]]) as usize;
+            let attr_type = u16::from_ne_bytes([attrs[2], attrs[3]]);
+            if attr_len < 4  ||  attr_len > attrs.len() {
+                return Err(invalid_data("truncated sock_diag attribute"));
+            }
+            let data = &attrs[4..attr_len];
+            match attr_type {
+                UNIX_DIAG_NAME => info.addr = UnixSocketAddr::from_raw_bytes(data)?,
+                UNIX_DIAG_PEER => info.peer_inode = read_struct::<u32>(data),
+                UNIX_DIAG_UID => info.uid = read_struct::<u32>(data),
+                UNIX_DIAG_RQLEN => if let Some([recv, send]) = read_struct::<[u32; 2]>(data) {
+                    info.recv_queue_len = Some(recv);
+                    info.send_queue_len = Some(send);
+                },
+                UNIX_DIAG_VFS => if let Some([inode, device]) = read_struct::<[u32; 2]>(data) {
+                    info.file_inode = Some(inode);
+                    info.file_device = Some(device);
+                },
+                _ => {/*not requested*/}
+            }
+            attrs = &attrs[align(attr_len).min(attrs.len())..];
+        }
+        Ok(info)
+    }
+}
+
+
+
+/// Closes the netlink socket even if sending or receiving fails.
+struct NetlinkSocket(RawFd);
+
+impl Drop for NetlinkSocket {
+    fn drop(&mut self) {
+        // ignore errors - unlikely and there is nowhere to return them
+        unsafe { close(self.0) };
+    }
+}
+
+/// Sends a `SOCK_DIAG_BY_FAMILY` request and collects the responses.
+fn query(flags: u16,  inode: u32) -> Result<Vec<UnixSocketInfo>, io::Error> {
+    let fd = cvt!(unsafe { socket(AF_NETLINK, SOCK_DGRAM | SOCK_CLOEXEC, NETLINK_SOCK_DIAG) })?;
+    let netlink = NetlinkSocket(fd);
+
+    let request = Request {
+        header: NlMsgHdr {
+            len: mem::size_of::<Request>() as u32,
+            msg_type: SOCK_DIAG_BY_FAMILY,
+            flags: NLM_F_REQUEST | flags,
+            seq: 1,
+            pid: 0,
+        },
+        body: UnixDiagReq {
+            family: AF_UNIX as u8,
+            protocol: 0,
+            pad: 0,
+            states: !0,
+            inode,
+            show: UDIAG_SHOW_NAME | UDIAG_SHOW_VFS | UDIAG_SHOW_PEER
+                | UDIAG_SHOW_RQLEN | UDIAG_SHOW_UID,
+            cookie: [!0, !0], // don't check
+        },
+    };
+    let request_ptr = &request as *const Request as *const c_void;
+    let request_len = mem::size_of::<Request>();
+    let sent = cvt_r!(unsafe { send(netlink.0, request_ptr, request_len, 0) })?;
+    if sent as usize != request_len {
+        return Err(io::Error::new(ErrorKind::WriteZero, "sock_diag request was truncated"));
+    }
+
+    let mut sockets = Vec::new();
+    let mut buf = vec![0u8; 32*1024];
+    loop {
+        let buf_ptr = buf.as_mut_ptr() as *mut c_void;
+        let received = cvt_r!(unsafe { recv(netlink.0, buf_ptr, buf.len(), 0) })?;
+        let mut messages = &buf[..received as usize];
+        while let Some(header) = read_struct::<NlMsgHdr>(messages) {
+            let msg_len = header.len as usize;
+            if msg_len < mem::size_of::<NlMsgHdr>()  ||  msg_len > messages.len() {
+                return Err(invalid_data("truncated netlink message"));
+            }
+            let payload = &messages[mem::size_of::<NlMsgHdr>()..msg_len];
+            match header.msg_type {
+                NLMSG_DONE => return Ok(sockets),
+                NLMSG_ERROR => {
+                    return match read_struct::<i32>(payload) {
+                        Some(0) => Ok(sockets),
+                        Some(errno) => Err(io::Error::from_raw_os_error(-errno)),
+                        None => Err(invalid_data("truncated netlink error")),
+                    };
+                }
+                SOCK_DIAG_BY_FAMILY => sockets.push(UnixSocketInfo::parse(payload)?),
+                _ => {/*ignore*/}
+            }
+            messages = &messages[align(msg_len).min(messages.len())..];
+        }
+        // a request for a single socket is not terminated by NLMSG_DONE
+        if flags & NLM_F_DUMP == 0  &&  !sockets.is_empty() {
+            return Ok(sockets);
+        }
+    }
+}
+
+/// Returns the inode of a socket, which `sock_diag` uses to identify it.
+fn socket_inode(fd: RawFd) -> Result<u32, io::Error> {
+    let mut stat = unsafe { mem::zeroed::<libc::stat>() };
+    cvt!(unsafe { fstat(fd, &mut stat) })?;
+    // socket inodes are allocated with get_next_ino(), which is 32-bit
+    Ok(stat.st_ino as u32)
+}
+
+
+
+/// Lists all unix domain sockets in the current network namespace.
+///
+/// # Examples
+///
+/// ```
+/// use uds::{diag, UnixSocketAddr, UnixSeqpacketListener};
+///
+/// let addr = UnixSocketAddr::new("@diag all_sockets").unwrap();
+/// let _listener = UnixSeqpacketListener::bind_unix_addr(&addr).unwrap();
+/// if let Ok(sockets) = diag::all_sockets() {
+///     assert!(sockets.iter().any(|socket| socket.addr == addr ));
+/// }
+/// ```
+pub fn all_sockets() -> Result<Vec<UnixSocketInfo>, io::Error> {
+    query(NLM_F_DUMP, 0)
+}
+
+/// Looks up the unix domain socket with the given inode.
+pub fn socket_by_inode(inode: u32) -> Result<UnixSocketInfo, io::Error> {
+    match query(0, inode)?.pop() {
+        Some(socket) => Ok(socket),
+        None => Err(io::Error::new(ErrorKind::NotFound, "no unix socket has this inode")),
+    }
+}
+
+/// Returns information about one of our own sockets.
+pub fn socket_info<S: AsRawFd>(socket: &S) -> Result<UnixSocketInfo, io::Error> {
+    socket_by_inode(socket_inode(socket.as_raw_fd())?)
+}
+
+/// Returns information about the socket on the other end of a connection.
+///
+/// An error of kind `NotConnected` is returned if the socket has no peer.
+pub fn peer_info<S: AsRawFd>(socket: &S) -> Result<UnixSocketInfo, io::Error> {
+    match socket_info(socket)?.peer_inode {
+        Some(peer) => socket_by_inode(peer),
+        None => Err(io::Error::new(ErrorKind::NotConnected, "the socket has no peer")),
+    }
+}
@@ -0,0 +1,112 @@
+use std::error::Error as StdError;
+use std::fmt::{self, Display};
+use std::io::{self, ErrorKind};
+
+/// Errors detected by this crate, as opposed to by the OS.
+///
+/// Functions in this crate return `io::Error` for compatibility with std,
+/// but the errors they create themselves can be recovered with
+/// [`Error::from_io()`](#method.from_io) or `Error::from()`.
+///
+/// # Examples
+///
+/// ```
+/// use uds::{Error, UnixSocketAddr};
+///
+/// let too_long = "x".repeat(UnixSocketAddr::max_path_len()+1);
+/// let error = UnixSocketAddr::from_path(&too_long).unwrap_err();
+/// assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+/// match Error::from_io(error) {
+///     Error::AddressTooLong{ max } => assert_eq!(max, UnixSocketAddr::max_path_len()),
+///     other => panic!("unexpected error {}", other),
+/// }
+/// ```
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// A path or abstract name is longer than `max` bytes.
+    AddressTooLong{ max: usize },
+    /// A path contains NUL bytes.
+    NulInPath,
+    /// Abstract addresses are not supported on this OS.
+    AbstractUnsupported,
+    /// More file descriptors than can be sent at once.
+    TooManyFds,
+    /// File descriptors sent with a message were not all received.
+    AncillaryTruncated,
+    /// An ancillary buffer is not aligned for `cmsghdr`, or is longer than the OS accepts.
+    InvalidAncillaryBuffer,
+    /// The operation is not supported on this OS.
+    UnsupportedOnPlatform,
+    /// An error from the OS, or one this crate doesn't have a variant for.
+    Os(io::Error),
+}
+
+impl Error {
+    /// Returns the `io::ErrorKind` this error has when converted to `io::Error`.
+    pub fn kind(&self) -> ErrorKind {
+        match self {
+            Error::AddressTooLong{ .. } => ErrorKind::InvalidInput,
+            Error::NulInPath => ErrorKind::InvalidInput,
+            Error::AbstractUnsupported => ErrorKind::AddrNotAvailable,
+            Error::TooManyFds => ErrorKind::InvalidInput,
+            Error::AncillaryTruncated => ErrorKind::InvalidData,
+            Error::InvalidAncillaryBuffer => ErrorKind::InvalidInput,
+            Error::UnsupportedOnPlatform => ErrorKind::Other,
+            Error::Os(error) => error.kind(),
+        }
+    }
+
+    /// Recovers the typed error from an `io::Error` returned by this crate.
+    ///
+    /// Errors not created by this crate become `Error::Os`.
+    pub fn from_io(error: io::Error) -> Self {
+        if error.get_ref().is_some_and(|inner| inner.is::<Error>() ) {
+            let inner = error.into_inner().unwrap();
+            return *inner.downcast::<Error>().unwrap();
+        }
+        Error::Os(error)
+    }
+}
+
+impl Display for Error {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::AddressTooLong{ max } => write!(fmtr, "address is too long (max {} bytes)", max),
+            Error::NulInPath => fmtr.write_str("path cannot contain nul bytes"),
+            Error::AbstractUnsupported => write!(fmtr,
+                    "abstract unix domain socket addresses are not available on {}",
+                    std::env::consts::OS
+            ),
+            Error::TooManyFds => fmtr.write_str("too many file descriptors"),
+            Error::AncillaryTruncated => fmtr.write_str("file descriptors were truncated"),
+            Error::InvalidAncillaryBuffer => fmtr.write_str("ancillary buffer is misaligned or too big"),
+            Error::UnsupportedOnPlatform => fmtr.write_str("not supported on this OS"),
+            Error::Os(error) => Display::fmt(error, fmtr),
+        }
+    }
+}
+
+impl StdError for Error {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self {
+            Error::Os(error) => error.source(),
+            _ => None,
+        }
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(error: Error) -> io::Error {
+        match error {
+            Error::Os(error) => error,
+            error => io::Error::new(error.kind(), error),
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Error {
+        Error::from_io(error)
+    }
+}
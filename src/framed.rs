@@ -0,0 +1,245 @@
+//! Length-delimited frames with attached file descriptors over stream sockets.
+//!
+//! Stream sockets don't preserve message boundaries, so to know which file
+//! descriptors belong to which message both need to be framed.
+//! Each frame starts with its length and the number of file descriptors,
+//! and the file descriptors are attached to the first byte of the frame.
+//! Frames are read exactly, so a read never reaches into the next frame
+//! and its file descriptors.
+//!
+//! Unlike seqpacket sockets, this works on all supported OSes, including
+//! macOS and OpenBSD.
+//! A tokio version is available as
+//! [`uds::tokio::FramedStream`](../tokio/struct.FramedStream.html).
+//!
+//! # Examples
+//!
+//! ```
+//! use std::os::unix::io::AsRawFd;
+//! use std::os::unix::net::UnixStream;
+//! use uds::framed::FramedStream;
+//!
+//! let (a, b) = UnixStream::pair().unwrap();
+//! let (mut a, mut b) = (FramedStream::new(a), FramedStream::new(b));
+//! a.send_frame(b"first", &[a.get_ref().as_raw_fd()]).unwrap();
+//! a.send_frame(b"second", &[]).unwrap();
+//!
+//! let (frame, fds) = b.recv_frame().unwrap();
+//! assert_eq!((&frame[..], fds.len()), (&b"first"[..], 1));
+//! let (frame, fds) = b.recv_frame().unwrap();
+//! assert_eq!((&frame[..], fds.len()), (&b"second"[..], 0));
+//! ```
+
+use std::fmt::{self, Debug};
+use std::io::{self, ErrorKind};
+use std::os::unix::io::{FromRawFd, OwnedFd, RawFd};
+
+use crate::{Error, UnixStreamExt};
+
+/// The most file descriptors that can be attached to one frame.
+///
+/// This is the limit on Linux.
+pub const MAX_FDS: usize = 253;
+/// frame length (u32) and number of file descriptors (u16)
+const HEADER_LEN: usize = 4 + 2;
+/// how much of the frame to allocate at a time, to not trust the length blindly
+const READ_CHUNK: usize = 64*1024;
+
+/// A frame and the file descriptors that were attached to it.
+pub(crate) type Frame = (Vec<u8>, Vec<OwnedFd>);
+
+/// The unsent part of a frame.
+#[derive(Default)]
+pub(crate) struct FrameWriter {
+    bytes: Vec<u8>,
+    sent: usize,
+    fds: Vec<RawFd>,
+}
+
+impl FrameWriter {
+    /// Whether a frame has been partially sent, and must be finished before
+    /// sending another.
+    pub(crate) fn is_partial(&self) -> bool {
+        self.sent != 0  &&  self.sent != self.bytes.len()
+    }
+
+    /// Replaces any frame that hasn't been started.
+    pub(crate) fn start(&mut self,  frame: &[u8],  fds: &[RawFd]) -> Result<(), io::Error> {
+        let len = u32::try_from(frame.len())
+            .map_err(|_| io::Error::new(ErrorKind::InvalidInput, "frame is too long") )?;
+        if fds.len() > MAX_FDS {
+            return Err(Error::TooManyFds.into());
+        }
+        self.bytes.clear();
+        self.bytes.extend_from_slice(&len.to_le_bytes());
+        self.bytes.extend_from_slice(&(fds.len() as u16).to_le_bytes());
+        self.bytes.extend_from_slice(frame);
+        self.sent = 0;
+        self.fds.clear();
+        self.fds.extend_from_slice(fds);
+        Ok(())
+    }
+
+    /// Sends the rest of the frame, attaching the file descriptors to the first byte.
+    pub(crate) fn write<S>(&mut self,  mut send: S) -> Result<(), io::Error>
+    where S: FnMut(&[u8], &[RawFd])->Result<usize, io::Error> {
+        while self.sent < self.bytes.len() {
+            let fds = if self.sent == 0 {&self.fds[..]} else {&[][..]};
+            match send(&self.bytes[self.sent..], fds) {
+                Ok(0) => return Err(ErrorKind::WriteZero.into()),
+                Ok(sent) => {
+                    self.sent += sent;
+                    self.fds.clear();
+                }
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The received part of a frame.
+#[derive(Default)]
+pub(crate) struct FrameReader {
+    header: [u8; HEADER_LEN],
+    header_received: usize,
+    frame: Vec<u8>,
+    frame_received: usize,
+    fds: Vec<OwnedFd>,
+}
+
+impl FrameReader {
+    /// Receives until a frame is complete, or returns `None` if the stream
+    /// was closed between frames.
+    ///
+    /// Errors such as `WouldBlock` can be retried, as the progress is kept.
+    pub(crate) fn read<R>(&mut self,  mut recv: R)
+    -> Result<Option<Frame>, io::Error>
+    where R: FnMut(&mut[u8], &mut[RawFd])->Result<(usize, usize), io::Error> {
+        let mut fd_buf = [-1; MAX_FDS];
+        while self.header_received < HEADER_LEN {
+            let (received, fds) = match recv(&mut self.header[self.header_received..], &mut fd_buf) {
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                result => result?,
+            };
+            self.fds.extend(fd_buf[..fds].iter().map(|&fd| unsafe { OwnedFd::from_raw_fd(fd) } ));
+            if received == 0  &&  self.header_received == 0  &&  self.fds.is_empty() {
+                return Ok(None);
+            } else if received == 0 {
+                return Err(self.eof());
+            }
+            self.header_received += received;
+            if self.header_received == HEADER_LEN {
+                self.check_fds()?;
+            }
+        }
+
+        let [a, b, c, d, _, _] = self.header;
+        let frame_len = u32::from_le_bytes([a, b, c, d]) as usize;
+        while self.frame_received < frame_len {
+            if self.frame.len() == self.frame_received {
+                let grow_to = frame_len.min(self.frame_received + READ_CHUNK);
+                self.frame.resize(grow_to, 0);
+            }
+            let (received, fds) = match recv(&mut self.frame[self.frame_received..], &mut fd_buf) {
+                Err(ref e) if e.kind() == ErrorKind::Interrupted => continue,
+                result => result?,
+            };
+            if fds != 0 {
+                for &fd in &fd_buf[..fds] {
+                    drop(unsafe { OwnedFd::from_raw_fd(fd) });
+                }
+                self.reset();
+                return Err(io::Error::new(
+                    ErrorKind::InvalidData,
+                    "file descriptors received in the middle of a frame"
+                ));
+            }
+            if received == 0 {
+                return Err(self.eof());
+            }
+            self.frame_received += received;
+        }
+
+        let frame = std::mem::take(&mut self.frame);
+        let fds = std::mem::take(&mut self.fds);
+        self.reset();
+        Ok(Some((frame, fds)))
+    }
+
+    fn check_fds(&mut self) -> Result<(), io::Error> {
+        let expected = u16::from_le_bytes([self.header[4], self.header[5]]) as usize;
+        if self.fds.len() != expected {
+            self.reset();
+            return Err(Error::AncillaryTruncated.into());
+        }
+        Ok(())
+    }
+
+    fn eof(&mut self) -> io::Error {
+        self.reset();
+        io::Error::new(ErrorKind::UnexpectedEof, "stream closed in the middle of a frame")
+    }
+
+    fn reset(&mut self) {
+        *self = FrameReader::default();
+    }
+}
+
+/// Sends and receives length-delimited frames with attached file descriptors
+/// over a stream socket.
+///
+/// The stream should be in blocking mode: if a frame is only partially sent
+/// when an error happens, the rest is sent before the next frame.
+/// Receiving keeps its progress on errors, so `recv_frame()` can be retried.
+///
+/// Receiving returns an error of kind `ErrorKind::UnexpectedEof` when the
+/// peer has closed the connection.
+pub struct FramedStream<S: UnixStreamExt> {
+    stream: S,
+    writer: FrameWriter,
+    reader: FrameReader,
+}
+
+impl<S: UnixStreamExt+Debug> Debug for FramedStream<S> {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_tuple("FramedStream").field(&self.stream).finish()
+    }
+}
+
+impl<S: UnixStreamExt> FramedStream<S> {
+    /// Wraps a connected stream socket.
+    pub fn new(stream: S) -> Self {
+        FramedStream { stream, writer: FrameWriter::default(), reader: FrameReader::default() }
+    }
+
+    /// Returns a reference to the stream.
+    pub fn get_ref(&self) -> &S {
+        &self.stream
+    }
+
+    /// Returns the stream, discarding any partially sent or received frame.
+    pub fn into_inner(self) -> S {
+        self.stream
+    }
+
+    /// Sends a frame, with `fds` attached to it.
+    pub fn send_frame(&mut self,  frame: &[u8],  fds: &[RawFd]) -> Result<(), io::Error> {
+        let stream = &self.stream;
+        if self.writer.is_partial() {
+            self.writer.write(|bytes, fds| stream.send_fds(bytes, fds) )?;
+        }
+        self.writer.start(frame, fds)?;
+        self.writer.write(|bytes, fds| stream.send_fds(bytes, fds) )
+    }
+
+    /// Receives a frame and the file descriptors attached to it.
+    pub fn recv_frame(&mut self) -> Result<(Vec<u8>, Vec<OwnedFd>), io::Error> {
+        let stream = &self.stream;
+        match self.reader.read(|buf, fd_buf| stream.recv_fds(buf, fd_buf) )? {
+            Some(received) => Ok(received),
+            None => Err(io::Error::new(ErrorKind::UnexpectedEof, "stream closed")),
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Passing a listening socket to another process, for restarting without
+//! refusing any connections.
+//!
+//! The old process calls [`send_listener()`](fn.send_listener.html) with a
+//! stream connected to the new process, which calls
+//! [`receive_listener()`](fn.receive_listener.html).
+//! The listener is sent with its type, address and nonblocking state,
+//! which the new process checks before acknowledging that it has taken over.
+//! Connections that arrive during the handoff wait in the listener's queue,
+//! which both processes share, so none are lost.
+//!
+//! Once `send_listener()` returns successfully the old process should stop
+//! accepting and drop its listener. Passing a
+//! [`ShutdownHandle`](../struct.ShutdownHandle.html) makes it stop threads in
+//! [`accept_unless_shutdown()`](../struct.UnixSeqpacketListener.html#method.accept_unless_shutdown),
+//! while letting connections they have already accepted finish.
+//!
+//! # Examples
+//!
+#![cfg_attr(not(target_vendor="apple"), doc="```")]
+#![cfg_attr(target_vendor="apple", doc="```no_run")]
+//! use std::os::unix::net::UnixStream;
+//! use uds::handoff::{self, Listener};
+//! use uds::{ShutdownHandle, UnixSeqpacketConn, UnixSeqpacketListener};
+//!
+//! let (to_new, to_old) = UnixStream::pair().unwrap();
+//! let (old_listener, temp) = UnixSeqpacketListener::bind_temp().unwrap().into_parts();
+//! let client = UnixSeqpacketConn::connect_unix_addr(temp.addr()).unwrap();
+//!
+//! let shutdown = ShutdownHandle::new().unwrap();
+//! let old = std::thread::spawn(move || {
+//!     handoff::send_listener(&to_new, &old_listener, Some(&shutdown)).unwrap();
+//!     assert!(shutdown.is_shutdown());
+//! });
+//!
+//! let (listener, addr) = handoff::receive_listener(&to_old).unwrap();
+//! assert_eq!(&addr, temp.addr());
+//! let listener = match listener {
+//!     Listener::Seqpacket(listener) => listener,
+//!     other => panic!("got {:?}", other),
+//! };
+//! listener.accept_unix_addr().unwrap(); // connected before the handoff
+//! old.join().unwrap();
+//! ```
+
+use std::io::{self, ErrorKind, Read, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd, IntoRawFd, OwnedFd, RawFd};
+use std::os::unix::net::{UnixListener, UnixStream};
+
+use libc::{SOCK_STREAM, SOCK_SEQPACKET};
+
+use crate::helpers::*;
+use crate::{nonblocking, ShutdownHandle, UnixSeqpacketListener, UnixSocketAddr, UnixStreamExt};
+
+const MAGIC: &[u8] = b"uds-handoff-1";
+const STREAM: u8 = b's';
+const SEQPACKET: u8 = b'p';
+const ACKNOWLEDGED: u8 = 1;
+/// magic, type, nonblocking and address length
+const HEADER_LEN: usize = MAGIC.len() + 3;
+
+/// A listener received from another process.
+#[derive(Debug)]
+pub enum Listener {
+    /// A stream listener, which might be in nonblocking mode.
+    Stream(UnixListener),
+    /// A blocking seqpacket listener.
+    Seqpacket(UnixSeqpacketListener),
+    /// A nonblocking seqpacket listener.
+    NonblockingSeqpacket(nonblocking::UnixSeqpacketListener),
+}
+
+impl Listener {
+    /// Returns the address the socket is listening on.
+    pub fn local_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        get_unix_addr(self.as_raw_fd(), GetAddr::LOCAL)
+    }
+}
+
+impl AsRawFd for Listener {
+    fn as_raw_fd(&self) -> RawFd {
+        match self {
+            Listener::Stream(listener) => listener.as_raw_fd(),
+            Listener::Seqpacket(listener) => listener.as_raw_fd(),
+            Listener::NonblockingSeqpacket(listener) => listener.as_raw_fd(),
+        }
+    }
+}
+
+impl IntoRawFd for Listener {
+    fn into_raw_fd(self) -> RawFd {
+        match self {
+            Listener::Stream(listener) => listener.into_raw_fd(),
+            Listener::Seqpacket(listener) => listener.into_raw_fd(),
+            Listener::NonblockingSeqpacket(listener) => listener.into_raw_fd(),
+        }
+    }
+}
+
+fn invalid_data(msg: &'static str) -> io::Error {
+    io::Error::new(ErrorKind::InvalidData, msg)
+}
+
+/// Sends a stream or seqpacket listener to the process at the other end of `control`,
+/// and waits for it to acknowledge taking over.
+///
+/// If `shutdown` is given, it is shut down once the other process has
+/// acknowledged, and not if this function fails.
+/// The listener is still usable by this process if the handoff fails.
+pub fn send_listener<L: AsRawFd+?Sized>
+(control: &UnixStream,  listener: &L,  shutdown: Option<&ShutdownHandle>)
+-> Result<(), io::Error> {
+    let mut control = control;
+    let fd = listener.as_raw_fd();
+    let kind = match socket_type(fd)? {
+        SOCK_STREAM => STREAM,
+        SOCK_SEQPACKET => SEQPACKET,
+        _ => return Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            "only stream and seqpacket listeners can be handed off"
+        )),
+    };
+    let addr = get_unix_addr(fd, GetAddr::LOCAL)?;
+    let nonblocking = is_nonblocking(fd)?;
+
+    let mut message = Vec::with_capacity(HEADER_LEN + addr.as_raw_bytes().len());
+    message.extend_from_slice(MAGIC);
+    message.push(kind);
+    message.push(nonblocking as u8);
+    message.push(addr.as_raw_bytes().len() as u8);
+    message.extend_from_slice(addr.as_raw_bytes());
+    let sent = control.send_fds(&message, &[fd])?;
+    control.write_all(&message[sent..])?;
+
+    let mut response = [0u8; 1];
+    match control.read(&mut response)? {
+        1 if response[0] == ACKNOWLEDGED => {}
+        1 => return Err(invalid_data("unexpected handoff response")),
+        _ => return Err(io::Error::new(
+            ErrorKind::ConnectionAborted,
+            "the other process did not take over the listener"
+        )),
+    }
+    if let Some(shutdown) = shutdown {
+        shutdown.shutdown()?;
+    }
+    Ok(())
+}
+
+/// Receives a listener sent with [`send_listener()`](fn.send_listener.html),
+/// checks that it matches what the other process described,
+/// and acknowledges taking over.
+///
+/// Returns the listener together with its address.
+pub fn receive_listener(control: &UnixStream) -> Result<(Listener, UnixSocketAddr), io::Error> {
+    let mut control = control;
+    let mut header = [0u8; HEADER_LEN];
+    let mut fd_buf = [-1; 2];
+    let (received, fds) = control.recv_fds(&mut header, &mut fd_buf)?;
+    // take ownership first so that they are closed on errors
+    let mut received_fds = fd_buf[..fds].iter().map(|&fd| unsafe { OwnedFd::from_raw_fd(fd) } );
+    let fd = match (received_fds.next(), received_fds.next()) {
+        (Some(fd), None) => fd,
+        (None, _) if received == 0 => return Err(io::Error::new(
+            ErrorKind::UnexpectedEof,
+            "the other process closed the connection"
+        )),
+        _ => return Err(invalid_data("expected exactly one file descriptor")),
+    };
+    control.read_exact(&mut header[received..])?;
+    let (magic, described) = header.split_at(MAGIC.len());
+    if magic != MAGIC {
+        return Err(invalid_data("not a handoff message"));
+    }
+    let (kind, nonblocking, addr_len) = (described[0], described[1] != 0, described[2]);
+    let mut addr_bytes = vec![0u8; addr_len as usize];
+    control.read_exact(&mut addr_bytes)?;
+    let addr = UnixSocketAddr::from_raw_bytes(&addr_bytes)?;
+
+    let expected_type = match kind {
+        STREAM => SOCK_STREAM,
+        SEQPACKET => SOCK_SEQPACKET,
+        _ => return Err(invalid_data("unknown listener type")),
+    };
+    if socket_type(fd.as_raw_fd())? != expected_type {
+        return Err(invalid_data("received listener has a different type than described"));
+    }
+    if is_nonblocking(fd.as_raw_fd())? != nonblocking {
+        return Err(invalid_data("received listener has a different nonblocking state than described"));
+    }
+    if get_unix_addr(fd.as_raw_fd(), GetAddr::LOCAL)? != addr {
+        return Err(invalid_data("received listener has a different address than described"));
+    }
+
+    let fd = fd.into_raw_fd();
+    let listener = unsafe { match (expected_type, nonblocking) {
+        (SOCK_STREAM, _) => Listener::Stream(UnixListener::from_raw_fd(fd)),
+        (_, false) => Listener::Seqpacket(UnixSeqpacketListener::from_raw_fd(fd)),
+        (_, true) => Listener::NonblockingSeqpacket(nonblocking::UnixSeqpacketListener::from_raw_fd(fd)),
+    }};
+    control.write_all(&[ACKNOWLEDGED])?;
+    Ok((listener, addr))
+}
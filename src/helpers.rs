@@ -7,16 +7,34 @@ use std::convert::TryInto;
 use std::os::unix::io::{RawFd, AsRawFd, IntoRawFd};
 use std::io::{self, ErrorKind};
 use std::mem;
-use std::time::Duration;
+use std::time::{Duration, Instant};
+use std::ffi::{CString, OsStr};
+use std::os::unix::ffi::OsStrExt;
+use std::path::{Path, PathBuf};
+#[cfg(not(any(target_os="linux", target_os="android")))]
+use std::sync::Mutex;
 
 use libc::{c_int, sockaddr, socklen_t, AF_UNIX};
 use libc::{bind, connect, getsockname, getpeername};
 use libc::{socket, accept, close, listen, socketpair};
-use libc::{ioctl, FIONBIO};
+use libc::{ioctl, FIONBIO, FIONREAD};
+use libc::{poll, pollfd, nfds_t, c_short};
+#[cfg(any(target_os="linux", target_os="android"))]
+use libc::TIOCOUTQ as SIOCOUTQ; // same value, SIOCOUTQ isn't exposed by libc
+#[cfg(target_os="freebsd")]
+use libc::FIONWRITE;
+#[cfg(target_vendor="apple")]
+use libc::SO_NWRITE;
 #[cfg(not(target_os = "haiku"))]
 use libc::{FIOCLEX,FIONCLEX};
 use libc::{fcntl, F_DUPFD_CLOEXEC, EINVAL, dup};
-use libc::{getsockopt, SOL_SOCKET, SO_ERROR, c_void};
+use libc::{open, O_DIRECTORY, O_CLOEXEC, AT_FDCWD};
+#[cfg(any(target_os="linux", target_os="android"))]
+use libc::O_PATH;
+#[cfg(not(any(target_os="linux", target_os="android")))]
+use libc::{O_RDONLY, fchdir};
+use libc::{getsockopt, SOL_SOCKET, SO_ERROR, SO_TYPE, c_void};
+use libc::{F_GETFL, O_NONBLOCK};
 #[cfg_attr(target_env="musl", allow(deprecated))]
 use libc::{setsockopt, SO_RCVTIMEO, SO_SNDTIMEO, timeval, time_t};
 #[cfg(any(target_os="illumos", target_os="solaris"))]
@@ -77,10 +95,27 @@ pub fn set_nonblocking(fd: RawFd,  nonblocking: bool) -> Result<(), io::Error> {
 
 
 
-pub struct SetAddr(unsafe extern "C" fn(RawFd, *const sockaddr, socklen_t) -> c_int);
+#[cfg(target_os="freebsd")]
+extern "C" {
+    // not exposed by libc
+    fn bindat(dirfd: c_int,  socket: RawFd,  addr: *const sockaddr,  len: socklen_t) -> c_int;
+    fn connectat(dirfd: c_int,  socket: RawFd,  addr: *const sockaddr,  len: socklen_t) -> c_int;
+}
+
+pub struct SetAddr(
+    unsafe extern "C" fn(RawFd, *const sockaddr, socklen_t) -> c_int,
+    #[cfg(target_os="freebsd")]
+    unsafe extern "C" fn(c_int, RawFd, *const sockaddr, socklen_t) -> c_int,
+);
 impl SetAddr {
+    #[cfg(not(target_os="freebsd"))]
     pub const LOCAL: Self = SetAddr(bind);
+    #[cfg(not(target_os="freebsd"))]
     pub const PEER: Self = SetAddr(connect);
+    #[cfg(target_os="freebsd")]
+    pub const LOCAL: Self = SetAddr(bind, bindat);
+    #[cfg(target_os="freebsd")]
+    pub const PEER: Self = SetAddr(connect, connectat);
 }
 /// Safe wrapper around `bind()` or `connect()`, that retries on EINTR.
 pub fn set_unix_addr(socket: RawFd,  set_side: SetAddr,  addr: &UnixSocketAddr)
@@ -100,6 +135,211 @@ pub fn set_unix_addr(socket: RawFd,  set_side: SetAddr,  addr: &UnixSocketAddr)
     }
 }
 
+/// Like `set_unix_addr()` for path addresses, but also supports paths
+/// longer than `sun_path`.
+///
+/// Long paths are handled by opening the parent directory and then
+/// binding or connecting to the file name relative to it:
+/// through `/proc/self/fd/N/` on Linux and Android,
+/// with `bindat()` / `connectat()` on FreeBSD,
+/// and by temporarily changing the working directory elsewhere.
+pub fn set_path_addr(socket: RawFd,  set_side: SetAddr,  path: &Path)
+-> Result<(), io::Error> {
+    #[cfg(target_os="freebsd")]
+    if is_long_path(path) {
+        let (dir, name) = split_path(path)?;
+        let dir = DirFd::open(dir)?;
+        return set_addr_in_dir(socket, set_side, dir.0, name);
+    }
+    with_path_addr(path, |addr| set_unix_addr(socket, set_side, addr) )
+}
+
+/// Binds or connects to a path relative to an open directory,
+/// like `bindat()` / `connectat()`.
+///
+/// Absolute paths and `AT_FDCWD` are passed on to `set_path_addr()`.
+pub fn set_addr_at(socket: RawFd,  set_side: SetAddr,  dir: RawFd,  name: &Path)
+-> Result<(), io::Error> {
+    #[cfg(target_os="freebsd")]
+    if !(name.is_absolute() || dir == AT_FDCWD || name.as_os_str().is_empty()) {
+        return set_addr_in_dir(socket, set_side, dir, name.as_os_str());
+    }
+    with_addr_at(dir, name, |addr| set_unix_addr(socket, set_side, addr) )
+}
+
+/// Calls `f` with an address for `path`, which can be longer than `sun_path`.
+///
+/// On Linux and Android long paths are replaced by `/proc/self/fd/N/name`,
+/// where `N` is the parent directory, which is kept open until `f` returns.
+/// The kernel remembers that path, so addresses it later reports for the
+/// socket are not usable.
+/// Other OSes temporarily change the working directory to the parent directory,
+/// see `with_addr_in_dir()`.
+pub fn with_path_addr<T, F>(path: &Path,  f: F) -> Result<T, io::Error>
+where F: FnOnce(&UnixSocketAddr)->Result<T, io::Error> {
+    if is_long_path(path) {
+        let (dir, name) = split_path(path)?;
+        let dir = DirFd::open(dir)?;
+        with_addr_in_dir(dir.0, name, f)
+    } else {
+        f(&UnixSocketAddr::from_path(path)?)
+    }
+}
+
+/// Calls `f` with an address for `name` relative to the open directory `dir`.
+///
+/// Absolute paths and `AT_FDCWD` are passed on to `with_path_addr()`.
+pub fn with_addr_at<T, F>(dir: RawFd,  name: &Path,  f: F) -> Result<T, io::Error>
+where F: FnOnce(&UnixSocketAddr)->Result<T, io::Error> {
+    if name.is_absolute() || dir == AT_FDCWD {
+        with_path_addr(name, f)
+    } else if name.as_os_str().is_empty() {
+        Err(io::Error::new(ErrorKind::NotFound, "path is empty"))
+    } else {
+        with_addr_in_dir(dir, name.as_os_str(), f)
+    }
+}
+
+/// Returns the path of the socket file `name` relative to the open directory `dir`.
+///
+/// Absolute paths and `AT_FDCWD` return `name` unchanged.
+pub fn path_at(dir: RawFd,  name: &Path) -> Result<PathBuf, io::Error> {
+    if name.is_absolute() || dir == AT_FDCWD {
+        Ok(name.to_path_buf())
+    } else {
+        Ok(dir_path(dir)?.join(name))
+    }
+}
+
+#[cfg(any(target_os="linux", target_os="android"))]
+fn dir_path(dir: RawFd) -> Result<PathBuf, io::Error> {
+    std::fs::read_link(format!("/proc/self/fd/{}", dir))
+}
+
+#[cfg(target_os="freebsd")]
+fn dir_path(dir: RawFd) -> Result<PathBuf, io::Error> {
+    use std::os::unix::ffi::OsStringExt;
+    use std::ffi::{CStr, OsString};
+    // struct kinfo_file, which libc doesn't expose for all supported versions
+    #[repr(C)]
+    struct KinfoFile {
+        structsize: c_int,
+        _fields: [u8; 364],
+        path: [libc::c_char; 1024],
+    }
+    const F_KINFO: c_int = 22;
+    unsafe {
+        let mut info: KinfoFile = mem::zeroed();
+        info.structsize = mem::size_of::<KinfoFile>() as c_int;
+        if fcntl(dir, F_KINFO, &mut info) == -1 {
+            // F_KINFO was added in FreeBSD 13.1
+            return in_dir(dir, std::env::current_dir);
+        }
+        let path = CStr::from_ptr(info.path.as_ptr()).to_bytes().to_vec();
+        Ok(PathBuf::from(OsString::from_vec(path)))
+    }
+}
+
+#[cfg(not(any(target_os="linux", target_os="android", target_os="freebsd")))]
+fn dir_path(dir: RawFd) -> Result<PathBuf, io::Error> {
+    in_dir(dir, std::env::current_dir)
+}
+
+fn is_long_path(path: &Path) -> bool {
+    path.as_os_str().len() > UnixSocketAddr::max_path_len()
+}
+
+/// Splits a path into its parent directory and file name.
+fn split_path(path: &Path) -> Result<(&Path, &OsStr), io::Error> {
+    match (path.parent(), path.file_name()) {
+        (Some(dir), Some(name)) if dir.as_os_str().is_empty() => Ok((Path::new("."), name)),
+        (Some(dir), Some(name)) => Ok((dir, name)),
+        _ => Err(io::Error::new(
+            ErrorKind::InvalidInput,
+            "path is too long for an unix socket address and has no file name"
+        )),
+    }
+}
+
+/// An open directory, closed on drop.
+struct DirFd(RawFd);
+
+impl Drop for DirFd {
+    fn drop(&mut self) {
+        unsafe { close(self.0) };
+    }
+}
+
+impl DirFd {
+    fn open(path: &Path) -> Result<Self, io::Error> {
+        let path = CString::new(path.as_os_str().as_bytes())?;
+        #[cfg(any(target_os="linux", target_os="android"))]
+        let flags = O_PATH | O_DIRECTORY | O_CLOEXEC;
+        #[cfg(not(any(target_os="linux", target_os="android")))]
+        let flags = O_RDONLY | O_DIRECTORY | O_CLOEXEC;
+        let fd = cvt_r!(unsafe { open(path.as_ptr(), flags) })?;
+        Ok(DirFd(fd))
+    }
+}
+
+#[cfg(any(target_os="linux", target_os="android"))]
+fn with_addr_in_dir<T, F>(dir: RawFd,  name: &OsStr,  f: F) -> Result<T, io::Error>
+where F: FnOnce(&UnixSocketAddr)->Result<T, io::Error> {
+    let mut via_proc = format!("/proc/self/fd/{}/", dir).into_bytes();
+    via_proc.extend_from_slice(name.as_bytes());
+    f(&UnixSocketAddr::from_path(OsStr::from_bytes(&via_proc))?)
+}
+
+#[cfg(not(any(target_os="linux", target_os="android")))]
+fn with_addr_in_dir<T, F>(dir: RawFd,  name: &OsStr,  f: F) -> Result<T, io::Error>
+where F: FnOnce(&UnixSocketAddr)->Result<T, io::Error> {
+    let addr = UnixSocketAddr::from_path(name)?;
+    in_dir(dir, || f(&addr) )
+}
+
+/// Calls `f` with the working directory temporarily changed to `dir`.
+///
+/// The working directory is shared by all threads, so this serializes
+/// changes to it from this crate, but other code can still observe it.
+#[cfg(not(any(target_os="linux", target_os="android")))]
+fn in_dir<T, F>(dir: RawFd,  f: F) -> Result<T, io::Error>
+where F: FnOnce()->Result<T, io::Error> {
+    static CHANGING_DIR: Mutex<()> = Mutex::new(());
+
+    let original = DirFd::open(Path::new("."))?;
+    let _guard = CHANGING_DIR.lock().unwrap_or_else(|poisoned| poisoned.into_inner() );
+    cvt!(unsafe { fchdir(dir) })?;
+    let result = f();
+    cvt!(unsafe { fchdir(original.0) })?;
+    result
+}
+
+#[cfg(target_os="freebsd")]
+fn set_addr_in_dir(socket: RawFd,  set_side: SetAddr,  dir: RawFd,  name: &OsStr)
+-> Result<(), io::Error> {
+    let addr = UnixSocketAddr::from_path(name)?;
+    unsafe {
+        let (addr, len) = addr.as_raw_general();
+        cvt_r!((set_side.1)(dir, socket, addr, len))?;
+    }
+    Ok(())
+}
+
+/// Calls `bind` with addresses from `UnixSocketAddr::unique_abstract()`
+/// until one isn't in use, and returns that address along with the result.
+pub fn bind_unique<T, F>(mut bind: F) -> Result<(T, UnixSocketAddr), io::Error>
+where F: FnMut(&UnixSocketAddr) -> Result<T, io::Error> {
+    let mut attempts = 0;
+    loop {
+        let addr = UnixSocketAddr::unique_abstract("uds")?;
+        match bind(&addr) {
+            Ok(bound) => break Ok((bound, addr)),
+            Err(ref e) if e.kind() == ErrorKind::AddrInUse && attempts < 10 => attempts += 1,
+            Err(e) => break Err(e),
+        }
+    }
+}
+
 pub struct GetAddr(unsafe extern "C" fn(RawFd, *mut sockaddr, *mut socklen_t) -> c_int);
 impl GetAddr {
     pub const LOCAL: Self = GetAddr(getsockname);
@@ -118,6 +358,44 @@ pub fn get_unix_addr(socket: RawFd,  get_side: GetAddr)
     }
 }
 
+/// Safe wrapper around `poll()` that retries on EINTR and
+/// waits until `deadline` if there is one.
+///
+/// Returns the number of ready file descriptors, which is zero on timeout.
+pub fn poll_until(fds: &mut[pollfd],  deadline: Option<Instant>) -> Result<usize, io::Error> {
+    loop {
+        let timeout_ms = match deadline {
+            None => -1,
+            Some(deadline) => {
+                let remaining = deadline.saturating_duration_since(Instant::now());
+                let mut ms = remaining.as_millis();
+                if remaining.subsec_nanos() % 1_000_000 != 0 {
+                    ms += 1; // round up to not return before the deadline
+                }
+                ms.min(c_int::MAX as u128) as c_int
+            }
+        };
+        match cvt!(unsafe { poll(fds.as_mut_ptr(), fds.len() as nfds_t, timeout_ms) }) {
+            // the timeout was capped
+            Ok(0) if deadline.is_some_and(|deadline| Instant::now() < deadline ) => {}
+            Ok(ready) => break Ok(ready as usize),
+            Err(ref e) if e.kind() == ErrorKind::Interrupted => {}
+            Err(e) => break Err(e),
+        }
+    }
+}
+
+/// Waits until `events` are ready on a single file descriptor,
+/// returning `false` if `timeout` elapses first.
+///
+/// Errors and hangups also count as ready, as operations won't block then.
+pub fn wait_ready(fd: RawFd,  events: c_short,  timeout: Option<Duration>)
+-> Result<bool, io::Error> {
+    let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout) );
+    let mut fds = [pollfd { fd, events, revents: 0 }];
+    Ok(poll_until(&mut fds, deadline)? != 0)
+}
+
 /// Safe wrapper around `getsockopt(SO_ERROR)`.
 pub fn take_error(socket: RawFd) -> Result<Option<io::Error>, io::Error> {
     unsafe {
@@ -140,6 +418,23 @@ pub fn take_error(socket: RawFd) -> Result<Option<io::Error>, io::Error> {
     }
 }
 
+/// Safe wrapper around `getsockopt(SO_TYPE)`.
+pub fn socket_type(socket: RawFd) -> Result<c_int, io::Error> {
+    unsafe {
+        let mut socket_type: c_int = 0;
+        let mut optlen = mem::size_of::<c_int>() as socklen_t;
+        let dst_ptr = &mut socket_type as *mut c_int as *mut c_void;
+        cvt!(getsockopt(socket, SOL_SOCKET, SO_TYPE, dst_ptr, &mut optlen))?;
+        Ok(socket_type)
+    }
+}
+
+/// Checks O_NONBLOCK with `fcntl()`.
+pub fn is_nonblocking(fd: RawFd) -> Result<bool, io::Error> {
+    let flags = cvt!(unsafe { fcntl(fd, F_GETFL) })?;
+    Ok(flags & O_NONBLOCK != 0)
+}
+
 #[repr(C)]
 pub struct TimeoutDirection(c_int);
 impl TimeoutDirection {
@@ -209,6 +504,103 @@ pub fn get_timeout(socket: RawFd,  direction: TimeoutDirection)
 
 
 
+/// Safe wrapper around `ioctl(FIONREAD)`, which Linux also calls `SIOCINQ`.
+pub fn recv_queue_len(socket: RawFd) -> Result<usize, io::Error> {
+    let mut queued: c_int = 0;
+    cvt!(unsafe { ioctl(socket, FIONREAD, &mut queued) })?;
+    Ok(queued as usize)
+}
+
+#[cfg(target_os="netbsd")]
+const FIONWRITE: libc::c_ulong = 0x40046679; // _IOR('f', 121, int), not in older libc
+
+/// Safe wrapper around `ioctl(SIOCOUTQ)` on Linux and `ioctl(FIONWRITE)` on FreeBSD and NetBSD.
+#[cfg(any(target_os="linux", target_os="android", target_os="freebsd", target_os="netbsd"))]
+pub fn send_queue_len(socket: RawFd) -> Result<usize, io::Error> {
+    #[cfg(any(target_os="linux", target_os="android"))]
+    let request = SIOCOUTQ;
+    #[cfg(any(target_os="freebsd", target_os="netbsd"))]
+    let request = FIONWRITE;
+    let mut queued: c_int = 0;
+    cvt!(unsafe { ioctl(socket, request, &mut queued) })?;
+    Ok(queued as usize)
+}
+
+/// Safe wrapper around `getsockopt(SO_NWRITE)`.
+#[cfg(target_vendor="apple")]
+pub fn send_queue_len(socket: RawFd) -> Result<usize, io::Error> {
+    unsafe {
+        let mut queued: c_int = 0;
+        let mut optlen = mem::size_of::<c_int>() as socklen_t;
+        let dst_ptr = &mut queued as *mut c_int as *mut c_void;
+        cvt!(getsockopt(socket, SOL_SOCKET, SO_NWRITE, dst_ptr, &mut optlen))?;
+        Ok(queued as usize)
+    }
+}
+
+#[cfg(not(any(
+    target_os="linux", target_os="android",
+    target_os="freebsd", target_os="netbsd",
+    target_vendor="apple",
+)))]
+pub fn send_queue_len(_socket: RawFd) -> Result<usize, io::Error> {
+    Err(crate::Error::UnsupportedOnPlatform.into())
+}
+
+
+
+// Not exposed by libc for all Linux targets
+#[cfg(all(
+    any(target_os="linux", target_os="android"),
+    not(any(target_arch="sparc", target_arch="sparc64"))
+))]
+const SO_PEEK_OFF: c_int = 42;
+#[cfg(all(target_os="linux", any(target_arch="sparc", target_arch="sparc64")))]
+const SO_PEEK_OFF: c_int = 0x26;
+
+/// Safe wrapper around `setsockopt(SO_PEEK_OFF)`.
+///
+/// `None` disables the peek offset, which is represented by -1.
+#[cfg(any(target_os="linux", target_os="android"))]
+pub fn set_peek_offset(socket: RawFd,  offset: Option<usize>) -> Result<(), io::Error> {
+    let offset: c_int = match offset {
+        Some(offset) => offset.try_into().map_err(|_| io::Error::new(
+            ErrorKind::InvalidInput,
+            "peek offset is too big"
+        ))?,
+        None => -1,
+    };
+    unsafe {
+        let offset_ptr = &offset as *const c_int as *const c_void;
+        let int_size = mem::size_of::<c_int>() as socklen_t;
+        cvt!(setsockopt(socket, SOL_SOCKET, SO_PEEK_OFF, offset_ptr, int_size))?;
+    }
+    Ok(())
+}
+/// Safe wrapper around `getsockopt(SO_PEEK_OFF)`.
+#[cfg(any(target_os="linux", target_os="android"))]
+pub fn get_peek_offset(socket: RawFd) -> Result<Option<usize>, io::Error> {
+    let offset = unsafe {
+        let mut offset: c_int = -1;
+        let offset_ptr = &mut offset as *mut c_int as *mut c_void;
+        let mut optlen = mem::size_of::<c_int>() as socklen_t;
+        cvt!(getsockopt(socket, SOL_SOCKET, SO_PEEK_OFF, offset_ptr, &mut optlen))?;
+        offset
+    };
+    Ok(if offset < 0 {None} else {Some(offset as usize)})
+}
+
+#[cfg(not(any(target_os="linux", target_os="android")))]
+pub fn set_peek_offset(_socket: RawFd,  _offset: Option<usize>) -> Result<(), io::Error> {
+    Err(crate::Error::UnsupportedOnPlatform.into())
+}
+#[cfg(not(any(target_os="linux", target_os="android")))]
+pub fn get_peek_offset(_socket: RawFd) -> Result<Option<usize>, io::Error> {
+    Err(crate::Error::UnsupportedOnPlatform.into())
+}
+
+
+
 /// Used in setup of sockets to ensure the file descriptor is always closed
 /// if later parts of the setup fails.
 pub struct Socket(RawFd);
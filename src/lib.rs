@@ -42,6 +42,12 @@ extern crate mio;
 extern crate mio_07;
 #[cfg(feature="mio_08")]
 extern crate mio_08;
+#[cfg(feature="mio_1")]
+extern crate mio_1;
+#[cfg(feature="serde")]
+extern crate serde;
+#[cfg(feature="channel")]
+extern crate bincode;
 
 /// Get errno as io::Error on -1.
 macro_rules! cvt {($syscall:expr) => {
@@ -66,18 +72,41 @@ macro_rules! cvt_r {($syscall:expr) => {
 }}
 
 mod addr;
+mod error;
 mod credentials;
 mod helpers;
 mod ancillary;
+mod peek;
+mod temp;
+mod shutdown;
+mod poller;
+mod command;
+mod capabilities;
 mod traits;
 mod seqpacket;
 #[cfg(feature="tokio")]
 pub mod tokio;
+#[cfg(feature="async-io")]
+pub mod async_io;
+#[cfg(any(target_os="linux", target_os="android"))]
+pub mod diag;
+pub mod handoff;
+pub mod framed;
+pub mod packet;
+#[cfg(feature="channel")]
+pub mod channel;
 
 pub use addr::{UnixSocketAddr, UnixSocketAddrRef, AddrName};
+pub use error::Error;
 pub use traits::{UnixListenerExt, UnixStreamExt, UnixDatagramExt};
 pub use seqpacket::{UnixSeqpacketListener, UnixSeqpacketConn};
-pub use credentials::ConnCredentials;
+pub use credentials::{ConnCredentials, ReceivedCredentials};
+pub use peek::PeekCursor;
+pub use temp::{TempSocketPath, TempBound};
+pub use shutdown::ShutdownHandle;
+pub use poller::{Poller, Interest, Event};
+pub use command::{ChildSocket, CommandSocketExt};
+pub use capabilities::{capabilities, Capabilities};
 
 pub mod nonblocking {
     pub use crate::seqpacket::NonblockingUnixSeqpacketListener as UnixSeqpacketListener;
@@ -0,0 +1,589 @@
+//! Packet connections that work on all supported OSes, by emulating
+//! `SOCK_SEQPACKET` with framing over a stream socket where necessary.
+//!
+//! [`PacketConn`](struct.PacketConn.html) and
+//! [`PacketListener`](struct.PacketListener.html) use real seqpacket sockets
+//! when the OS supports them, and otherwise stream sockets with the framing
+//! from [`uds::framed`](../framed/index.html).
+//! Either way packet boundaries are kept, file descriptors stay with the
+//! packet they were sent with, and peer credentials are available.
+//!
+//! Connecting with the default constructors also falls back to a stream
+//! socket if the listener turns out to be one, so a listener using emulation
+//! can be reached from OSes that have seqpacket sockets.
+//! Emulation can be forced with the `_with()` constructors, to test that
+//! code path on OSes that don't need it.
+//!
+//! This module also has traits for code that should work with any packet
+//! socket: [`PacketSend`](trait.PacketSend.html),
+//! [`PacketRecv`](trait.PacketRecv.html) and [`FdPassing`](trait.FdPassing.html)
+//! are implemented by the seqpacket types, `PacketConn` and connected
+//! datagram sockets from `std` and `mio`,
+//! [`PeerCredentials`](trait.PeerCredentials.html) by all of them except
+//! the datagram sockets, and the `Async` variants by `uds::tokio::UnixSeqpacketConn`.
+//!
+//! # Examples
+//!
+//! ```
+//! use uds::packet::{PacketConn, Transport};
+//!
+//! let (a, b) = PacketConn::pair_with(Transport::Stream).unwrap();
+//! a.send(b"first").unwrap();
+//! a.send(b"second").unwrap();
+//!
+//! let mut buf = [0; 10];
+//! assert_eq!(b.recv(&mut buf).unwrap(), 5);
+//! assert_eq!(&buf[..5], b"first");
+//! assert_eq!(b.recv(&mut buf).unwrap(), 6);
+//! assert_eq!(&buf[..6], b"second");
+//! ```
+
+use std::fmt::{self, Debug};
+use std::io::{self, ErrorKind, IoSlice, IoSliceMut};
+use std::net::Shutdown;
+use std::os::unix::io::{AsFd, AsRawFd, BorrowedFd, FromRawFd, IntoRawFd, RawFd};
+use std::os::unix::net::{UnixDatagram, UnixListener, UnixStream};
+use std::path::Path;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::time::Duration;
+
+use libc::{SOCK_STREAM, EOPNOTSUPP, EPROTONOSUPPORT, EPROTOTYPE, ESOCKTNOSUPPORT};
+
+use crate::framed::{FrameReader, FrameWriter};
+use crate::helpers::*;
+use crate::ancillary::*;
+use crate::credentials::*;
+use crate::temp::{TempBound, bind_temp};
+use crate::{nonblocking, UnixListenerExt, UnixSeqpacketConn, UnixSeqpacketListener};
+use crate::{UnixDatagramExt, UnixSocketAddr, UnixStreamExt};
+
+/// Sends packets to a connected peer.
+pub trait PacketSend {
+    /// Sends a packet, and returns how many bytes of it were sent.
+    fn send_packet(&self,  packet: &[u8]) -> Result<usize, io::Error>;
+}
+
+/// Receives packets from a connected peer.
+pub trait PacketRecv {
+    /// Receives a packet, and returns how many bytes were stored in `buffer`.
+    ///
+    /// The rest of the packet is discarded if `buffer` is too short.
+    fn recv_packet(&self,  buffer: &mut[u8]) -> Result<usize, io::Error>;
+}
+
+/// Sends and receives packets with file descriptors attached.
+pub trait FdPassing: PacketSend + PacketRecv {
+    /// Sends a packet with `fds` attached.
+    fn send_packet_fds(&self,  packet: &[u8],  fds: &[RawFd]) -> Result<usize, io::Error>;
+    /// Receives a packet and the file descriptors attached to it.
+    ///
+    /// Returns the number of bytes received, whether the packet was truncated
+    /// and the number of file descriptors received.
+    fn recv_packet_fds(&self,  buffer: &mut[u8],  fd_buffer: &mut[RawFd])
+    -> Result<(usize, bool, usize), io::Error>;
+}
+
+/// Identifies the process at the other end of a connection.
+pub trait PeerCredentials {
+    /// Returns the credentials of the peer when the connection was established.
+    fn peer_credentials(&self) -> Result<ConnCredentials, io::Error>;
+}
+
+/// Sends packets to a connected peer without blocking.
+pub trait AsyncPacketSend {
+    /// Attempts to send a packet, registering the current task for wakeup
+    /// if the socket isn't ready.
+    fn poll_send_packet(self: Pin<&mut Self>,  cx: &mut Context<'_>,  packet: &[u8])
+    -> Poll<Result<usize, io::Error>>;
+}
+
+/// Receives packets from a connected peer without blocking.
+pub trait AsyncPacketRecv {
+    /// Attempts to receive a packet, registering the current task for wakeup
+    /// if none is available.
+    fn poll_recv_packet(self: Pin<&mut Self>,  cx: &mut Context<'_>,  buffer: &mut[u8])
+    -> Poll<Result<usize, io::Error>>;
+}
+
+/// Sends and receives packets with file descriptors attached without blocking.
+pub trait AsyncFdPassing: AsyncPacketSend + AsyncPacketRecv {
+    /// Attempts to send a packet with `fds` attached.
+    fn poll_send_packet_fds(self: Pin<&mut Self>,  cx: &mut Context<'_>,
+            packet: &[u8],  fds: &[RawFd]
+    ) -> Poll<Result<usize, io::Error>>;
+    /// Attempts to receive a packet and the file descriptors attached to it.
+    fn poll_recv_packet_fds(self: Pin<&mut Self>,  cx: &mut Context<'_>,
+            buffer: &mut[u8],  fd_buffer: &mut[RawFd]
+    ) -> Poll<Result<(usize, bool, usize), io::Error>>;
+}
+
+macro_rules! impl_packet_traits {($type:ty) => {
+    impl PacketSend for $type {
+        fn send_packet(&self,  packet: &[u8]) -> Result<usize, io::Error> {
+            self.send(packet)
+        }
+    }
+    impl PacketRecv for $type {
+        fn recv_packet(&self,  buffer: &mut[u8]) -> Result<usize, io::Error> {
+            self.recv(buffer)
+        }
+    }
+    impl FdPassing for $type {
+        fn send_packet_fds(&self,  packet: &[u8],  fds: &[RawFd]) -> Result<usize, io::Error> {
+            self.send_fds(packet, fds)
+        }
+        fn recv_packet_fds(&self,  buffer: &mut[u8],  fd_buffer: &mut[RawFd])
+        -> Result<(usize, bool, usize), io::Error> {
+            self.recv_fds(buffer, fd_buffer)
+        }
+    }
+    impl PeerCredentials for $type {
+        fn peer_credentials(&self) -> Result<ConnCredentials, io::Error> {
+            self.initial_peer_credentials()
+        }
+    }
+}}
+
+impl_packet_traits!{UnixSeqpacketConn}
+impl_packet_traits!{nonblocking::UnixSeqpacketConn}
+impl_packet_traits!{PacketConn}
+
+/// Implements the blocking packet traits for connected datagram sockets.
+macro_rules! impl_datagram_packet_traits {($type:ty) => {
+    impl PacketSend for $type {
+        fn send_packet(&self,  packet: &[u8]) -> Result<usize, io::Error> {
+            UnixDatagramExt::send_fds(self, packet, &[])
+        }
+    }
+    impl PacketRecv for $type {
+        fn recv_packet(&self,  buffer: &mut[u8]) -> Result<usize, io::Error> {
+            self.recv_packet_fds(buffer, &mut[]).map(|(received, _, _)| received )
+        }
+    }
+    impl FdPassing for $type {
+        fn send_packet_fds(&self,  packet: &[u8],  fds: &[RawFd]) -> Result<usize, io::Error> {
+            UnixDatagramExt::send_fds(self, packet, fds)
+        }
+        fn recv_packet_fds(&self,  buffer: &mut[u8],  fd_buffer: &mut[RawFd])
+        -> Result<(usize, bool, usize), io::Error> {
+            recv_fds(self.as_raw_fd(), None, &mut[IoSliceMut::new(buffer)], fd_buffer)
+        }
+    }
+}}
+
+impl_datagram_packet_traits!{UnixDatagram}
+#[cfg(feature="mio-uds")]
+impl_datagram_packet_traits!{mio_uds::UnixDatagram}
+#[cfg(feature="mio_07")]
+impl_datagram_packet_traits!{mio_07::net::UnixDatagram}
+#[cfg(feature="mio_08")]
+impl_datagram_packet_traits!{mio_08::net::UnixDatagram}
+#[cfg(feature="mio_1")]
+impl_datagram_packet_traits!{mio_1::net::UnixDatagram}
+
+#[cfg(feature="tokio")]
+impl AsyncPacketSend for crate::tokio::UnixSeqpacketConn {
+    fn poll_send_packet(self: Pin<&mut Self>,  cx: &mut Context<'_>,  packet: &[u8])
+    -> Poll<Result<usize, io::Error>> {
+        self.poll_send_priv(cx, |conn| conn.send(packet) )
+    }
+}
+#[cfg(feature="tokio")]
+impl AsyncPacketRecv for crate::tokio::UnixSeqpacketConn {
+    fn poll_recv_packet(self: Pin<&mut Self>,  cx: &mut Context<'_>,  buffer: &mut[u8])
+    -> Poll<Result<usize, io::Error>> {
+        self.poll_recv_priv(cx, |conn| conn.recv(buffer) )
+    }
+}
+#[cfg(feature="tokio")]
+impl AsyncFdPassing for crate::tokio::UnixSeqpacketConn {
+    fn poll_send_packet_fds(self: Pin<&mut Self>,  cx: &mut Context<'_>,
+            packet: &[u8],  fds: &[RawFd]
+    ) -> Poll<Result<usize, io::Error>> {
+        self.poll_send_priv(cx, |conn| conn.send_fds(packet, fds) )
+    }
+    fn poll_recv_packet_fds(self: Pin<&mut Self>,  cx: &mut Context<'_>,
+            buffer: &mut[u8],  fd_buffer: &mut[RawFd]
+    ) -> Poll<Result<(usize, bool, usize), io::Error>> {
+        self.poll_recv_priv(cx, |conn| conn.recv_fds(buffer, fd_buffer) )
+    }
+}
+#[cfg(feature="tokio")]
+impl PeerCredentials for crate::tokio::UnixSeqpacketConn {
+    fn peer_credentials(&self) -> Result<ConnCredentials, io::Error> {
+        self.initial_peer_credentials()
+    }
+}
+
+/// The kind of socket a packet connection or listener uses.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Transport {
+    /// A `SOCK_SEQPACKET` socket.
+    Seqpacket,
+    /// A `SOCK_STREAM` socket with framing.
+    Stream,
+}
+
+/// Whether an error means seqpacket sockets aren't supported by the OS,
+/// or that the listener connected to is a stream socket. (`EPROTOTYPE`)
+fn seqpacket_unsupported(error: &io::Error) -> bool {
+    let unsupported = [EPROTONOSUPPORT, ESOCKTNOSUPPORT, EOPNOTSUPP, EPROTOTYPE];
+    error.raw_os_error().is_some_and(|errno| unsupported.contains(&errno) )
+}
+
+/// Whether connecting to an abstract address might have failed because the
+/// listener is a stream socket.
+///
+/// Linux looks up abstract names separately for each socket type,
+/// so this is indistinguishable from there being no listener.
+fn abstract_type_mismatch(addr: &UnixSocketAddr,  error: &io::Error) -> bool {
+    addr.is_abstract()  &&  error.kind() == ErrorKind::ConnectionRefused
+}
+
+/// Framing state shared between clones of an emulated connection.
+#[derive(Default)]
+struct Framing {
+    writer: Mutex<FrameWriter>,
+    reader: Mutex<FrameReader>,
+}
+
+enum Conn {
+    Seqpacket(UnixSeqpacketConn),
+    Stream(UnixStream, Arc<Framing>),
+}
+
+/// A packet connection, using a seqpacket socket if supported or
+/// a stream socket with framing otherwise.
+///
+/// When emulated, a packet that was only partly sent when an error or
+/// write timeout happened is completed before sending the next one,
+/// and a partly received packet is continued by the next receive.
+/// The connection is always in blocking mode.
+pub struct PacketConn {
+    conn: Conn,
+}
+
+impl Debug for PacketConn {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_struct("PacketConn")
+            .field("fd", &self.as_raw_fd())
+            .field("transport", &self.transport())
+            .finish()
+    }
+}
+
+impl PacketConn {
+    fn emulated(stream: UnixStream) -> Self {
+        PacketConn { conn: Conn::Stream(stream, Arc::default()) }
+    }
+
+    /// Connects to a packet listener at `path`.
+    pub fn connect<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
+        match UnixSeqpacketConn::connect(path.as_ref()) {
+            Ok(conn) => Ok(PacketConn { conn: Conn::Seqpacket(conn) }),
+            Err(ref e) if seqpacket_unsupported(e) => {
+                UnixStream::connect_to_path(path).map(Self::emulated)
+            }
+            Err(e) => Err(e),
+        }
+    }
+    /// Connects to a packet listener at `addr`.
+    pub fn connect_unix_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error> {
+        match UnixSeqpacketConn::connect_unix_addr(addr) {
+            Ok(conn) => Ok(PacketConn { conn: Conn::Seqpacket(conn) }),
+            Err(ref e) if seqpacket_unsupported(e) || abstract_type_mismatch(addr, e) => {
+                UnixStream::connect_to_unix_addr(addr).map(Self::emulated)
+            }
+            Err(e) => Err(e),
+        }
+    }
+    /// Connects to a packet listener at `addr` using a specific kind of socket.
+    pub fn connect_unix_addr_with(addr: &UnixSocketAddr,  transport: Transport)
+    -> Result<Self, io::Error> {
+        match transport {
+            Transport::Seqpacket => UnixSeqpacketConn::connect_unix_addr(addr)
+                .map(|conn| PacketConn { conn: Conn::Seqpacket(conn) } ),
+            Transport::Stream => UnixStream::connect_to_unix_addr(addr).map(Self::emulated),
+        }
+    }
+
+    /// Creates a pair of packet connections connected to each other.
+    pub fn pair() -> Result<(Self, Self), io::Error> {
+        match Self::pair_with(Transport::Seqpacket) {
+            Err(ref e) if seqpacket_unsupported(e) => Self::pair_with(Transport::Stream),
+            result => result,
+        }
+    }
+    /// Creates a pair of connected packet connections using a specific kind of socket.
+    pub fn pair_with(transport: Transport) -> Result<(Self, Self), io::Error> {
+        match transport {
+            Transport::Seqpacket => {
+                let (a, b) = UnixSeqpacketConn::pair()?;
+                Ok((PacketConn { conn: Conn::Seqpacket(a) }, PacketConn { conn: Conn::Seqpacket(b) }))
+            }
+            Transport::Stream => {
+                let (a, b) = Socket::pair(SOCK_STREAM, false)?;
+                let a = unsafe { UnixStream::from_raw_fd(a.into_raw_fd()) };
+                let b = unsafe { UnixStream::from_raw_fd(b.into_raw_fd()) };
+                Ok((Self::emulated(a), Self::emulated(b)))
+            }
+        }
+    }
+
+    /// Returns whether this connection uses a seqpacket socket or emulates one.
+    pub fn transport(&self) -> Transport {
+        match self.conn {
+            Conn::Seqpacket(_) => Transport::Seqpacket,
+            Conn::Stream(..) => Transport::Stream,
+        }
+    }
+
+    /// Returns the address of this side of the connection.
+    pub fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        get_unix_addr(self.as_raw_fd(), GetAddr::LOCAL)
+    }
+    /// Returns the address of the other side of the connection.
+    pub fn peer_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        get_unix_addr(self.as_raw_fd(), GetAddr::PEER)
+    }
+    /// Returns information about the process of the peer when the connection was established.
+    pub fn initial_peer_credentials(&self) -> Result<ConnCredentials, io::Error> {
+        peer_credentials(self.as_raw_fd())
+    }
+
+    /// Sends a packet to the peer.
+    pub fn send(&self,  packet: &[u8]) -> Result<usize, io::Error> {
+        self.send_fds(packet, &[])
+    }
+    /// Receives a packet from the peer.
+    ///
+    /// The rest of the packet is discarded if `buffer` is too short,
+    /// and so are any file descriptors sent with it.
+    pub fn recv(&self,  buffer: &mut[u8]) -> Result<usize, io::Error> {
+        self.recv_fds(buffer, &mut[]).map(|(received, _, _)| received )
+    }
+    /// Sends a packet with associated file descriptors.
+    pub fn send_fds(&self,  bytes: &[u8],  fds: &[RawFd]) -> Result<usize, io::Error> {
+        match &self.conn {
+            Conn::Seqpacket(conn) => conn.send_fds(bytes, fds),
+            Conn::Stream(stream, framing) => {
+                let mut writer = framing.writer.lock().unwrap();
+                let mut send = |bytes: &[u8], fds: &[RawFd]| {
+                    send_ancillary(stream.as_raw_fd(), None, 0, &[IoSlice::new(bytes)], fds, None)
+                };
+                if writer.is_partial() {
+                    writer.write(&mut send)?;
+                }
+                writer.start(bytes, fds)?;
+                writer.write(&mut send)?;
+                Ok(bytes.len())
+            }
+        }
+    }
+    /// Receives a packet and associated file descriptors.
+    ///
+    /// The returned `bool` indicates whether the packet was truncated due to
+    /// too short buffer.
+    /// File descriptors that don't fit in `fd_buffer` are closed.
+    pub fn recv_fds(&self,  byte_buffer: &mut[u8],  fd_buffer: &mut[RawFd])
+    -> Result<(usize, bool, usize), io::Error> {
+        match &self.conn {
+            Conn::Seqpacket(conn) => conn.recv_fds(byte_buffer, fd_buffer),
+            Conn::Stream(stream, framing) => {
+                let mut reader = framing.reader.lock().unwrap();
+                let received = reader.read(|buf, fd_buf| stream.recv_fds(buf, fd_buf) )?;
+                let (frame, fds) = match received {
+                    Some(received) => received,
+                    None => return Ok((0, false, 0)),
+                };
+                let len = frame.len().min(byte_buffer.len());
+                byte_buffer[..len].copy_from_slice(&frame[..len]);
+                let mut num_fds = 0;
+                for (fd, slot) in fds.into_iter().zip(fd_buffer.iter_mut()) {
+                    *slot = fd.into_raw_fd();
+                    num_fds += 1;
+                }
+                Ok((len, len < frame.len(), num_fds))
+            }
+        }
+    }
+
+    /// Sets a read timeout, or removes it if `None`.
+    pub fn set_read_timeout(&self,  timeout: Option<Duration>) -> Result<(), io::Error> {
+        match &self.conn {
+            Conn::Seqpacket(conn) => conn.set_read_timeout(timeout),
+            Conn::Stream(stream, _) => stream.set_read_timeout(timeout),
+        }
+    }
+    /// Returns the read timeout, if any.
+    pub fn read_timeout(&self) -> Result<Option<Duration>, io::Error> {
+        match &self.conn {
+            Conn::Seqpacket(conn) => conn.read_timeout(),
+            Conn::Stream(stream, _) => stream.read_timeout(),
+        }
+    }
+    /// Sets a write timeout, or removes it if `None`.
+    pub fn set_write_timeout(&self,  timeout: Option<Duration>) -> Result<(), io::Error> {
+        match &self.conn {
+            Conn::Seqpacket(conn) => conn.set_write_timeout(timeout),
+            Conn::Stream(stream, _) => stream.set_write_timeout(timeout),
+        }
+    }
+    /// Returns the write timeout, if any.
+    pub fn write_timeout(&self) -> Result<Option<Duration>, io::Error> {
+        match &self.conn {
+            Conn::Seqpacket(conn) => conn.write_timeout(),
+            Conn::Stream(stream, _) => stream.write_timeout(),
+        }
+    }
+
+    /// Returns the value of the `SO_ERROR` option.
+    pub fn take_error(&self) -> Result<Option<io::Error>, io::Error> {
+        take_error(self.as_raw_fd())
+    }
+    /// Creates a new file descriptor also pointing to this side of this connection.
+    ///
+    /// When emulated, the clones share the state of partially sent and
+    /// received packets.
+    pub fn try_clone(&self) -> Result<Self, io::Error> {
+        let conn = match &self.conn {
+            Conn::Seqpacket(conn) => Conn::Seqpacket(conn.try_clone()?),
+            Conn::Stream(stream, framing) => Conn::Stream(stream.try_clone()?, framing.clone()),
+        };
+        Ok(PacketConn { conn })
+    }
+    /// Shuts down the read, write, or both halves of this connection.
+    pub fn shutdown(&self,  how: Shutdown) -> Result<(), io::Error> {
+        match &self.conn {
+            Conn::Seqpacket(conn) => conn.shutdown(how),
+            Conn::Stream(stream, _) => stream.shutdown(how),
+        }
+    }
+}
+
+impl AsRawFd for PacketConn {
+    fn as_raw_fd(&self) -> RawFd {
+        match &self.conn {
+            Conn::Seqpacket(conn) => conn.as_raw_fd(),
+            Conn::Stream(stream, _) => stream.as_raw_fd(),
+        }
+    }
+}
+
+impl AsFd for PacketConn {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw(self.as_raw_fd()) }
+    }
+}
+
+enum Listener {
+    Seqpacket(UnixSeqpacketListener),
+    Stream(UnixListener),
+}
+
+/// A listener for packet connections, using a seqpacket socket if supported
+/// or a stream socket otherwise.
+///
+/// Accepted connections use the same kind of socket as the listener.
+pub struct PacketListener {
+    listener: Listener,
+}
+
+impl Debug for PacketListener {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_struct("PacketListener")
+            .field("fd", &self.as_raw_fd())
+            .field("transport", &self.transport())
+            .finish()
+    }
+}
+
+impl PacketListener {
+    /// Creates a socket that listens for packet connections on `path`.
+    pub fn bind<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
+        match UnixSeqpacketListener::bind(path.as_ref()) {
+            Ok(listener) => Ok(PacketListener { listener: Listener::Seqpacket(listener) }),
+            Err(ref e) if seqpacket_unsupported(e) => UnixListener::bind_path(path)
+                .map(|(listener, _)| PacketListener { listener: Listener::Stream(listener) } ),
+            Err(e) => Err(e),
+        }
+    }
+    /// Creates a socket that listens for packet connections on `addr`.
+    pub fn bind_unix_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error> {
+        match Self::bind_unix_addr_with(addr, Transport::Seqpacket) {
+            Err(ref e) if seqpacket_unsupported(e) => {
+                Self::bind_unix_addr_with(addr, Transport::Stream)
+            }
+            result => result,
+        }
+    }
+    /// Creates a socket that listens for packet connections on `addr`,
+    /// using a specific kind of socket.
+    pub fn bind_unix_addr_with(addr: &UnixSocketAddr,  transport: Transport)
+    -> Result<Self, io::Error> {
+        let listener = match transport {
+            Transport::Seqpacket => Listener::Seqpacket(UnixSeqpacketListener::bind_unix_addr(addr)?),
+            Transport::Stream => Listener::Stream(UnixListener::bind_unix_addr(addr)?),
+        };
+        Ok(PacketListener { listener })
+    }
+    /// Creates a listener on a new temporary path, which is removed after
+    /// the returned listener is dropped.
+    pub fn bind_temp() -> Result<TempBound<Self>, io::Error> {
+        bind_temp(Self::bind_unix_addr)
+    }
+    /// Creates a listener using a specific kind of socket on a new temporary path.
+    pub fn bind_temp_with(transport: Transport) -> Result<TempBound<Self>, io::Error> {
+        bind_temp(|addr| Self::bind_unix_addr_with(addr, transport) )
+    }
+
+    /// Returns whether this listener uses a seqpacket socket or emulates one.
+    pub fn transport(&self) -> Transport {
+        match self.listener {
+            Listener::Seqpacket(_) => Transport::Seqpacket,
+            Listener::Stream(_) => Transport::Stream,
+        }
+    }
+    /// Returns the address the socket is listening on.
+    pub fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
+        get_unix_addr(self.as_raw_fd(), GetAddr::LOCAL)
+    }
+    /// Accepts a new incoming connection to this listener.
+    pub fn accept_unix_addr(&self) -> Result<(PacketConn, UnixSocketAddr), io::Error> {
+        match &self.listener {
+            Listener::Seqpacket(listener) => listener.accept_unix_addr()
+                .map(|(conn, addr)| (PacketConn { conn: Conn::Seqpacket(conn) }, addr) ),
+            Listener::Stream(listener) => listener.accept_unix_addr()
+                .map(|(stream, addr)| (PacketConn::emulated(stream), addr) ),
+        }
+    }
+    /// Returns the value of the `SO_ERROR` option.
+    pub fn take_error(&self) -> Result<Option<io::Error>, io::Error> {
+        take_error(self.as_raw_fd())
+    }
+    /// Creates a new file descriptor listening for the same connections.
+    pub fn try_clone(&self) -> Result<Self, io::Error> {
+        let listener = match &self.listener {
+            Listener::Seqpacket(listener) => Listener::Seqpacket(listener.try_clone()?),
+            Listener::Stream(listener) => Listener::Stream(listener.try_clone()?),
+        };
+        Ok(PacketListener { listener })
+    }
+}
+
+impl AsRawFd for PacketListener {
+    fn as_raw_fd(&self) -> RawFd {
+        match &self.listener {
+            Listener::Seqpacket(listener) => listener.as_raw_fd(),
+            Listener::Stream(listener) => listener.as_raw_fd(),
+        }
+    }
+}
+
+impl AsFd for PacketListener {
+    fn as_fd(&self) -> BorrowedFd<'_> {
+        unsafe { BorrowedFd::borrow_raw(self.as_raw_fd()) }
+    }
+}
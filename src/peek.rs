@@ -0,0 +1,94 @@
+use std::io::{self, ErrorKind};
+use std::marker::PhantomData;
+use std::os::unix::io::RawFd;
+
+use libc::{MSG_PEEK, c_void, recv};
+
+use crate::helpers::*;
+
+/// Incrementally peeks at the incoming data of a socket without removing it.
+///
+/// Created by [`UnixSeqpacketConn::peek_cursor()`](struct.UnixSeqpacketConn.html#method.peek_cursor)
+/// or [`UnixStreamExt::peek_cursor()`](trait.UnixStreamExt.html#method.peek_cursor).
+///
+/// Each [`peek_next()`](#method.peek_next) continues where the previous one
+/// stopped, so a header and then a body can be inspected without reading
+/// the header twice. This is implemented with the `SO_PEEK_OFF` socket option,
+/// which is only available on Linux and Android.
+/// The peek offset is disabled again when the cursor is dropped.
+///
+/// The offset is a property of the socket and not of the file descriptor,
+/// so it is shared with any clones of the socket.
+/// Receiving normally while a cursor exists moves the offset back by the
+/// number of bytes received, so that it keeps pointing at the same data.
+///
+/// # Examples
+///
+/// Route a connection based on its first bytes:
+///
+#[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+#[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+/// use std::io::Write;
+/// use std::os::unix::net::UnixStream;
+/// use uds::UnixStreamExt;
+///
+/// let (mut client, server) = UnixStream::pair().unwrap();
+/// client.write_all(b"GET /index.html").unwrap();
+///
+/// let mut cursor = server.peek_cursor().unwrap();
+/// let mut method = [0; 4];
+/// assert_eq!(cursor.peek_next(&mut method).unwrap(), 4);
+/// assert_eq!(&method, b"GET ");
+/// let mut path = [0; 20];
+/// let len = cursor.peek_next(&mut path).unwrap();
+/// assert_eq!(&path[..len], b"/index.html");
+/// drop(cursor);
+///
+/// // nothing has been consumed
+/// let mut request = [0; 20];
+/// let len = server.peek_cursor().unwrap().peek_next(&mut request).unwrap();
+/// assert_eq!(&request[..len], b"GET /index.html");
+/// ```
+#[derive(Debug)]
+pub struct PeekCursor<'a> {
+    fd: RawFd,
+    _socket: PhantomData<&'a ()>,
+}
+
+impl<'a> PeekCursor<'a> {
+    /// Enables `SO_PEEK_OFF` on the socket, starting at the first queued byte.
+    pub(crate) fn new(fd: RawFd) -> Result<Self, io::Error> {
+        set_peek_offset(fd, Some(0))?;
+        Ok(PeekCursor { fd, _socket: PhantomData })
+    }
+
+    /// Reads the data following what has already been peeked at,
+    /// without removing it from the incoming queue.
+    ///
+    /// Like `peek()`, this blocks if there is no data past the current
+    /// offset, unless the socket is nonblocking.
+    pub fn peek_next(&mut self,  buf: &mut[u8]) -> Result<usize, io::Error> {
+        let ptr = buf.as_mut_ptr() as *mut c_void;
+        let flags = MSG_NOSIGNAL | MSG_PEEK;
+        let peeked = cvt_r!(unsafe { recv(self.fd, ptr, buf.len(), flags) })?;
+        Ok(peeked as usize)
+    }
+
+    /// Returns how many bytes from the start of the incoming queue
+    /// the next peek will start at.
+    pub fn offset(&self) -> Result<usize, io::Error> {
+        get_peek_offset(self.fd).map(|offset| offset.unwrap_or(0) )
+    }
+
+    /// Makes the next peek start from the beginning of the incoming queue again.
+    pub fn rewind(&mut self) -> Result<(), io::Error> {
+        set_peek_offset(self.fd, Some(0))
+    }
+}
+
+impl<'a> Drop for PeekCursor<'a> {
+    fn drop(&mut self) {
+        // ignore errors - unlikely and there is nowhere to return them
+        let _ = set_peek_offset(self.fd, None);
+    }
+}
@@ -0,0 +1,187 @@
+use std::fmt::{self, Debug};
+use std::io;
+use std::marker::PhantomData;
+use std::os::unix::io::AsRawFd;
+use std::time::{Duration, Instant};
+
+use libc::{pollfd, c_short, POLLIN, POLLOUT, POLLHUP, POLLERR, POLLNVAL};
+
+use crate::helpers::*;
+
+/// Which kinds of readiness a [`Poller`](struct.Poller.html) should watch for.
+#[derive(Clone,Copy, PartialEq,Eq, Debug)]
+pub enum Interest {
+    /// Data or a connection to receive.
+    Readable,
+    /// Room to send.
+    Writable,
+    /// Either of the above.
+    Both,
+}
+
+impl Interest {
+    fn events(self) -> c_short {
+        match self {
+            Interest::Readable => POLLIN,
+            Interest::Writable => POLLOUT,
+            Interest::Both => POLLIN | POLLOUT,
+        }
+    }
+}
+
+/// Readiness of a socket, reported by [`Poller::events()`](struct.Poller.html#method.events).
+#[derive(Clone,Copy, PartialEq,Eq, Debug)]
+pub struct Event {
+    token: usize,
+    revents: c_short,
+}
+
+impl Event {
+    /// The token the socket was added with.
+    pub fn token(&self) -> usize {
+        self.token
+    }
+    /// Whether receiving (or accepting) won't block.
+    ///
+    /// Also true if the peer has closed the connection.
+    pub fn is_readable(&self) -> bool {
+        self.revents & (POLLIN | POLLHUP) != 0
+    }
+    /// Whether sending won't block.
+    pub fn is_writable(&self) -> bool {
+        self.revents & POLLOUT != 0
+    }
+    /// Whether the peer has closed the connection.
+    pub fn is_hangup(&self) -> bool {
+        self.revents & POLLHUP != 0
+    }
+    /// Whether the socket has a pending error, or is not an open file descriptor.
+    ///
+    /// Use `take_error()` on the socket to get the error.
+    pub fn is_error(&self) -> bool {
+        self.revents & (POLLERR | POLLNVAL) != 0
+    }
+}
+
+/// Waits for any of multiple sockets to become ready, using `poll()`.
+///
+/// This works with the blocking socket types of both this crate and std,
+/// or anything else that implements `AsRawFd`.
+/// Sockets are borrowed for as long as the poller exists,
+/// so that they cannot be closed while being polled.
+///
+/// Sockets are identified by a token chosen when adding them.
+/// Unlike with mio, the readiness is level-triggered and sockets don't
+/// need to be nonblocking.
+///
+/// # Examples
+///
+#[cfg_attr(not(target_vendor="apple"), doc="```")]
+#[cfg_attr(target_vendor="apple", doc="```no_run")]
+/// use std::os::unix::net::UnixStream;
+/// use std::time::Duration;
+/// use uds::{UnixSeqpacketConn, Poller, Interest};
+///
+/// let (stream_a, _stream_b) = UnixStream::pair().unwrap();
+/// let (packet_a, packet_b) = UnixSeqpacketConn::pair().unwrap();
+/// packet_b.send(b"ping").unwrap();
+///
+/// let mut poller = Poller::new();
+/// poller.add(&stream_a, 0, Interest::Readable);
+/// poller.add(&packet_a, 1, Interest::Readable);
+/// assert_eq!(poller.poll(Some(Duration::from_secs(1))).unwrap(), 1);
+/// let ready = poller.events().map(|event| event.token() ).collect::<Vec<_>>();
+/// assert_eq!(ready, [1]);
+/// ```
+#[derive(Default)]
+pub struct Poller<'a> {
+    fds: Vec<pollfd>,
+    tokens: Vec<usize>,
+    _sockets: PhantomData<&'a ()>,
+}
+
+impl<'a> Debug for Poller<'a> {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        let mut list = fmtr.debug_list();
+        for (fd, token) in self.fds.iter().zip(&self.tokens) {
+            list.entry(&format_args!("{} (fd {}, events {:#x})", token, fd.fd, fd.events));
+        }
+        list.finish()
+    }
+}
+
+impl<'a> Poller<'a> {
+    /// Creates an empty poller.
+    pub fn new() -> Self {
+        Poller { fds: Vec::new(), tokens: Vec::new(), _sockets: PhantomData }
+    }
+
+    /// Starts watching a socket for readiness.
+    ///
+    /// Adding the same socket more than once is allowed,
+    /// and will report it once for every token.
+    pub fn add<S: AsRawFd+?Sized>(&mut self,  socket: &'a S,  token: usize,  interest: Interest) {
+        let fd = socket.as_raw_fd();
+        self.fds.push(pollfd { fd, events: interest.events(), revents: 0 });
+        self.tokens.push(token);
+    }
+
+    /// Changes what to wait for on the sockets added with `token`.
+    ///
+    /// Returns `false` if there are no such sockets.
+    pub fn modify(&mut self,  token: usize,  interest: Interest) -> bool {
+        let mut found = false;
+        for (fd, _) in self.fds.iter_mut().zip(&self.tokens).filter(|&(_, &t)| t == token ) {
+            fd.events = interest.events();
+            found = true;
+        }
+        found
+    }
+
+    /// Stops watching the sockets added with `token`.
+    ///
+    /// Returns `false` if there were no such sockets.
+    pub fn remove(&mut self,  token: usize) -> bool {
+        let before = self.tokens.len();
+        let mut i = 0;
+        while i < self.tokens.len() {
+            if self.tokens[i] == token {
+                self.tokens.swap_remove(i);
+                self.fds.swap_remove(i);
+            } else {
+                i += 1;
+            }
+        }
+        self.tokens.len() != before
+    }
+
+    /// Returns the number of sockets being watched.
+    pub fn len(&self) -> usize {
+        self.fds.len()
+    }
+
+    /// Returns whether no sockets are being watched.
+    pub fn is_empty(&self) -> bool {
+        self.fds.is_empty()
+    }
+
+    /// Waits until at least one socket is ready, or `timeout` elapses.
+    ///
+    /// Returns the number of ready sockets, which is zero if the timeout
+    /// elapsed. `None` waits indefinitely.
+    pub fn poll(&mut self,  timeout: Option<Duration>) -> Result<usize, io::Error> {
+        let deadline = timeout.and_then(|timeout| Instant::now().checked_add(timeout) );
+        for fd in &mut self.fds {
+            fd.revents = 0;
+        }
+        poll_until(&mut self.fds, deadline)
+    }
+
+    /// Returns the sockets that were ready after the last
+    /// [`poll()`](#method.poll).
+    pub fn events(&self) -> impl Iterator<Item=Event> + '_ {
+        self.fds.iter().zip(&self.tokens)
+            .filter(|&(fd, _)| fd.revents != 0 )
+            .map(|(fd, &token)| Event { token, revents: fd.revents } )
+    }
+}
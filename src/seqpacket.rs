@@ -1,11 +1,12 @@
 use std::io::{self, ErrorKind, IoSlice, IoSliceMut};
 use std::mem;
 use std::net::Shutdown;
-use std::os::unix::io::{RawFd, FromRawFd, AsRawFd, IntoRawFd};
-use std::path::Path;
-use std::time::Duration;
+use std::os::unix::io::{RawFd, FromRawFd, AsRawFd, IntoRawFd, AsFd, BorrowedFd};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 
 use libc::{SOCK_SEQPACKET, MSG_EOR, MSG_PEEK, c_void, close, send, recv};
+use libc::{pollfd, POLLIN, POLLOUT};
 
 #[cfg(feature = "mio")]
 use mio::{event::Evented, unix::EventedFd, Poll, PollOpt, Ready, Token as Token_06};
@@ -16,10 +17,16 @@ use mio_07::{event::Source, unix::SourceFd, Interest, Registry, Token as Token_0
 #[cfg(feature = "mio_08")]
 use mio_08::{event::Source as Source_08, unix::SourceFd as SourceFd_08, Interest as Interest_08, Registry as Registry_08, Token as Token_08};
 
+#[cfg(feature = "mio_1")]
+use mio_1::{event::Source as Source_1, unix::SourceFd as SourceFd_1, Interest as Interest_1, Registry as Registry_1, Token as Token_1};
+
 use crate::addr::*;
 use crate::helpers::*;
 use crate::ancillary::*;
 use crate::credentials::*;
+use crate::peek::PeekCursor;
+use crate::temp::{TempBound, bind_temp};
+use crate::shutdown::ShutdownHandle;
 
 /// Implements traits apropriate for any file-descriptor-wrapping type.
 macro_rules! impl_rawfd_traits {($type:tt) => {
@@ -33,6 +40,11 @@ macro_rules! impl_rawfd_traits {($type:tt) => {
             self.fd
         }
     }
+    impl AsFd for $type {
+        fn as_fd(&self) -> BorrowedFd<'_> {
+            unsafe { BorrowedFd::borrow_raw(self.fd) }
+        }
+    }
     impl IntoRawFd for $type {
         fn into_raw_fd(self) -> RawFd {
             let fd = self.fd;
@@ -123,6 +135,36 @@ macro_rules! impl_mio_if_enabled {($type:tt) => {
             SourceFd_08(&self.fd).deregister(registry)
         }
     }
+
+    #[cfg(feature = "mio_1")]
+    impl Source_1 for $type {
+        fn register(&mut self,  registry: &Registry_1,  token: Token_1,  interest: Interest_1)
+        -> Result<(), io::Error> {
+            SourceFd_1(&self.fd).register(registry, token, interest)
+        }
+        fn reregister(&mut self,  registry: &Registry_1,  token: Token_1,  interest: Interest_1)
+        -> Result<(), io::Error> {
+            SourceFd_1(&self.fd).reregister(registry, token, interest)
+        }
+        fn deregister(&mut self,  registry: &Registry_1) -> Result<(), io::Error> {
+            SourceFd_1(&self.fd).deregister(registry)
+        }
+    }
+
+    #[cfg(feature = "mio_1")]
+    impl<'a> Source_1 for &'a $type {
+        fn register(&mut self,  registry: &Registry_1,  token: Token_1,  interest: Interest_1)
+        -> Result<(), io::Error> {
+            SourceFd_1(&self.fd).register(registry, token, interest)
+        }
+        fn reregister(&mut self,  registry: &Registry_1,  token: Token_1,  interest: Interest_1)
+        -> Result<(), io::Error> {
+            SourceFd_1(&self.fd).reregister(registry, token, interest)
+        }
+        fn deregister(&mut self,  registry: &Registry_1) -> Result<(), io::Error> {
+            SourceFd_1(&self.fd).deregister(registry)
+        }
+    }
 }}
 
 
@@ -213,11 +255,24 @@ impl_rawfd_traits!{UnixSeqpacketConn}
 impl UnixSeqpacketConn {
     /// Connects to an unix seqpacket server listening at `path`.
     ///
-    /// This is a wrapper around [`connect_unix_addr()`](#method.connect_unix_addr)
-    /// for convenience and compatibility with std.
+    /// Unlike [`connect_unix_addr()`](#method.connect_unix_addr), this also
+    /// supports paths longer than
+    /// [`UnixSocketAddr::max_path_len()`](struct.UnixSocketAddr.html#method.max_path_len)
+    /// by connecting relative to the parent directory.
     pub fn connect<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
-        let addr = UnixSocketAddr::from_path(&path)?;
-        Self::connect_unix_addr(&addr)
+        let socket = Socket::new(SOCK_SEQPACKET, false)?;
+        set_path_addr(socket.as_raw_fd(), SetAddr::PEER, path.as_ref())?;
+        Ok(UnixSeqpacketConn { fd: socket.into_raw_fd() })
+    }
+    /// Connects to an unix seqpacket server listening at `name`,
+    /// relative to the open directory `dirfd`.
+    ///
+    /// This avoids races with renames of the directory, like `connectat()`.
+    /// Absolute paths ignore `dirfd`.
+    pub fn connect_at<P: AsRef<Path>>(dirfd: RawFd,  name: P) -> Result<Self, io::Error> {
+        let socket = Socket::new(SOCK_SEQPACKET, false)?;
+        set_addr_at(socket.as_raw_fd(), SetAddr::PEER, dirfd, name.as_ref())?;
+        Ok(UnixSeqpacketConn { fd: socket.into_raw_fd() })
     }
     /// Connects to an unix seqpacket server listening at `addr`.
     pub fn connect_unix_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error> {
@@ -233,6 +288,15 @@ impl UnixSeqpacketConn {
         set_unix_addr(socket.as_raw_fd(), SetAddr::PEER, to)?;
         Ok(UnixSeqpacketConn { fd: socket.into_raw_fd() })
     }
+    /// Binds to an unused abstract address before connecting to a listening
+    /// seqpacket socket, and returns the address it got.
+    ///
+    /// See [`UnixSocketAddr::unique_abstract()`](struct.UnixSocketAddr.html#method.unique_abstract)
+    /// for how the address is chosen. This only works on Linux and Android.
+    pub fn connect_autobound(to: &UnixSocketAddr)
+    -> Result<(Self, UnixSocketAddr), io::Error> {
+        bind_unique(|from| Self::connect_from_to_unix_addr(from, to) )
+    }
 
     /// Creates a pair of unix-domain seqpacket conneections connected to each other.
     ///
@@ -352,6 +416,95 @@ impl UnixSeqpacketConn {
         recv_ancillary(self.fd, None, MSG_PEEK, buffers, &mut[])
             .map(|(bytes, ancillary)| (bytes, ancillary.message_truncated()) )
     }
+    /// Starts peeking incrementally at incoming packets, without removing them.
+    ///
+    /// Successive peeks through the returned cursor continue into the same
+    /// packet and then the following ones.
+    /// This is only supported on Linux and Android.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+    /// let (a, b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// a.send(b"header").unwrap();
+    /// a.send(b"body").unwrap();
+    ///
+    /// let mut cursor = b.peek_cursor().unwrap();
+    /// let mut buf = [0u8; 6];
+    /// assert_eq!(cursor.peek_next(&mut buf).unwrap(), 6);
+    /// assert_eq!(&buf, b"header");
+    /// assert_eq!(cursor.peek_next(&mut buf).unwrap(), 4);
+    /// assert_eq!(&buf[..4], b"body");
+    /// drop(cursor);
+    ///
+    /// assert_eq!(b.recv(&mut buf).unwrap(), 6);
+    /// assert_eq!(&buf, b"header");
+    /// ```
+    pub fn peek_cursor(&self) -> Result<PeekCursor<'_>, io::Error> {
+        PeekCursor::new(self.fd)
+    }
+
+    /// Returns the number of bytes in packets waiting to be received.
+    ///
+    /// This is the combined size of all queued packets on Linux,
+    /// and uses `FIONREAD` on other OSes.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+    /// let (a, b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// assert_eq!(b.recv_queue_len().unwrap(), 0);
+    /// a.send(b"hello").unwrap();
+    /// a.send(b"there").unwrap();
+    /// assert_eq!(b.recv_queue_len().unwrap(), 10);
+    /// b.recv(&mut[0; 10]).unwrap();
+    /// assert_eq!(b.recv_queue_len().unwrap(), 5);
+    /// ```
+    pub fn recv_queue_len(&self) -> Result<usize, io::Error> {
+        recv_queue_len(self.fd)
+    }
+    /// Returns the number of bytes sent but not yet received by the peer.
+    ///
+    /// On Linux the kernel counts the memory used by the queued packets,
+    /// which includes some bookkeeping overhead, so the value is only
+    /// useful for comparing with zero or earlier values.
+    /// FreeBSD and NetBSD (`FIONWRITE`) count data in the send buffer,
+    /// which unix domain sockets mostly bypass.
+    /// An error is returned on OSes without any equivalent.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+    /// let (a, b) = uds::UnixSeqpacketConn::pair().unwrap();
+    /// assert_eq!(a.send_queue_len().unwrap(), 0);
+    /// a.send(b"hello").unwrap();
+    /// assert!(a.send_queue_len().unwrap() >= 5);
+    /// b.recv(&mut[0; 10]).unwrap();
+    /// assert_eq!(a.send_queue_len().unwrap(), 0);
+    /// ```
+    pub fn send_queue_len(&self) -> Result<usize, io::Error> {
+        send_queue_len(self.fd)
+    }
+
+    /// Blocks until there is a packet to receive, or `timeout` elapses.
+    ///
+    /// Returns `false` if the timeout elapsed. `None` waits indefinitely.  
+    /// A closed connection also counts as readable.  
+    /// This uses `poll()`, and is not affected by
+    /// [`set_read_timeout()`](#method.set_read_timeout).
+    pub fn wait_readable(&self,  timeout: Option<Duration>) -> Result<bool, io::Error> {
+        wait_ready(self.fd, POLLIN, timeout)
+    }
+    /// Blocks until there is room to send a packet without blocking,
+    /// or `timeout` elapses.
+    ///
+    /// Returns `false` if the timeout elapsed. `None` waits indefinitely.
+    pub fn wait_writable(&self,  timeout: Option<Duration>) -> Result<bool, io::Error> {
+        wait_ready(self.fd, POLLOUT, timeout)
+    }
 
     /// Returns the value of the `SO_ERROR` option.
     ///
@@ -555,6 +708,26 @@ impl UnixSeqpacketConn {
         set_nonblocking(self.fd, nonblocking)
     }
 
+    /// Enables nonblocking mode and converts this connection into the
+    /// nonblocking type, so that it can be used with an event loop.
+    ///
+    /// The socket is closed if enabling nonblocking mode fails.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// # use std::io::ErrorKind;
+    /// # use uds::UnixSeqpacketConn;
+    /// let (a, _b) = UnixSeqpacketConn::pair().expect("create seqpacket pair");
+    /// let a = a.into_nonblocking().unwrap();
+    /// assert_eq!(a.recv(&mut[0; 20]).unwrap_err().kind(), ErrorKind::WouldBlock);
+    /// ```
+    pub fn into_nonblocking(self) -> Result<NonblockingUnixSeqpacketConn, io::Error> {
+        set_nonblocking(self.fd, true)?;
+        Ok(NonblockingUnixSeqpacketConn { fd: self.into_raw_fd() })
+    }
+
     /// Shuts down the read, write, or both halves of this connection.
     pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
         let how = match how {
@@ -594,9 +767,31 @@ pub struct UnixSeqpacketListener {
 impl_rawfd_traits!{UnixSeqpacketListener}
 impl UnixSeqpacketListener {
     /// Creates a socket that listens for seqpacket connections on the specified socket file.
+    ///
+    /// Paths longer than
+    /// [`UnixSocketAddr::max_path_len()`](struct.UnixSocketAddr.html#method.max_path_len)
+    /// are bound relative to their parent directory.
+    /// [`local_unix_addr()`](#method.local_unix_addr) can't represent those,
+    /// so connect to the listener with `path`.
     pub fn bind<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
-        let addr = UnixSocketAddr::from_path(path.as_ref())?;
-        Self::bind_unix_addr(&addr)
+        let socket = Socket::new(SOCK_SEQPACKET, false)?;
+        set_path_addr(socket.as_raw_fd(), SetAddr::LOCAL, path.as_ref())?;
+        socket.start_listening()?;
+        Ok(UnixSeqpacketListener { fd: socket.into_raw_fd() })
+    }
+    /// Creates a socket that listens for seqpacket connections on the
+    /// socket file `name`, relative to the open directory `dirfd`.
+    ///
+    /// This avoids races with renames of the directory, like `bindat()`.
+    /// Absolute paths ignore `dirfd`.
+    /// Returns the path of the socket file at the time it was created,
+    /// as [`local_unix_addr()`](#method.local_unix_addr) doesn't contain it on Linux.
+    pub fn bind_at<P: AsRef<Path>>(dirfd: RawFd,  name: P) -> Result<(Self, PathBuf), io::Error> {
+        let path = path_at(dirfd, name.as_ref())?;
+        let socket = Socket::new(SOCK_SEQPACKET, false)?;
+        set_addr_at(socket.as_raw_fd(), SetAddr::LOCAL, dirfd, name.as_ref())?;
+        socket.start_listening()?;
+        Ok((UnixSeqpacketListener { fd: socket.into_raw_fd() }, path))
     }
     /// Creates a socket that listens for seqpacket connections on the specified address.
     pub fn bind_unix_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error> {
@@ -605,6 +800,23 @@ impl UnixSeqpacketListener {
         socket.start_listening()?;
         Ok(UnixSeqpacketListener { fd: socket.into_raw_fd() })
     }
+    /// Creates a socket that listens for seqpacket connections on an unused
+    /// abstract address, and returns the address.
+    ///
+    /// See [`UnixSocketAddr::unique_abstract()`](struct.UnixSocketAddr.html#method.unique_abstract)
+    /// for how the address is chosen. This only works on Linux and Android.
+    pub fn autobind() -> Result<(Self, UnixSocketAddr), io::Error> {
+        bind_unique(Self::bind_unix_addr)
+    }
+    /// Creates a socket that listens for seqpacket connections on a temporary address.
+    ///
+    /// This uses an abstract address where supported, and a socket file in
+    /// a private temporary directory elsewhere.
+    /// The returned [`TempBound`](struct.TempBound.html) derefs to the listener,
+    /// and removes the file after closing it.
+    pub fn bind_temp() -> Result<TempBound<Self>, io::Error> {
+        bind_temp(Self::bind_unix_addr)
+    }
 
     /// Returns the address the socket is listening on.
     pub fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
@@ -619,6 +831,87 @@ impl UnixSeqpacketListener {
         Ok((conn, addr))
     }
 
+    /// Accepts a new incoming connection, or returns an error of kind
+    /// `TimedOut` if none arrive within `timeout`.
+    ///
+    /// Unlike [`set_timeout()`](#method.set_timeout) this uses `poll()`,
+    /// and works on all OSes.  
+    /// If another thread accepts the connection between `poll()` and `accept()`,
+    /// this waits for the next connection, which can be past the deadline.
+    /// Use a nonblocking listener to avoid that when multiple threads accept from it.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// # use std::io::ErrorKind;
+    /// # use std::time::Duration;
+    /// # use uds::UnixSeqpacketListener;
+    /// let listener = UnixSeqpacketListener::bind_temp().unwrap();
+    /// let err = listener.accept_timeout(Duration::from_millis(10)).unwrap_err();
+    /// assert_eq!(err.kind(), ErrorKind::TimedOut);
+    /// ```
+    pub fn accept_timeout(&self,  timeout: Duration)
+    -> Result<(UnixSeqpacketConn, UnixSocketAddr), io::Error> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.accept_until(deadline),
+            None => self.accept_unix_addr(),
+        }
+    }
+    /// Accepts a new incoming connection, or returns an error of kind
+    /// `TimedOut` if none arrive before `deadline`.
+    ///
+    /// See [`accept_timeout()`](#method.accept_timeout) for details.
+    pub fn accept_until(&self,  deadline: Instant)
+    -> Result<(UnixSeqpacketConn, UnixSocketAddr), io::Error> {
+        let mut fds = [pollfd { fd: self.fd, events: POLLIN, revents: 0 }];
+        self.poll_and_accept(&mut fds, Some(deadline))?
+            .ok_or_else(|| io::Error::new(ErrorKind::TimedOut, "accept timed out") )
+    }
+    /// Blocks until there is a connection to accept, or `timeout` elapses.
+    ///
+    /// Returns `false` if the timeout elapsed. `None` waits indefinitely.
+    pub fn wait_readable(&self,  timeout: Option<Duration>) -> Result<bool, io::Error> {
+        wait_ready(self.fd, POLLIN, timeout)
+    }
+    /// Accepts a new incoming connection, or returns `None` once
+    /// [`shutdown()`](struct.ShutdownHandle.html#method.shutdown)
+    /// is called on `shutdown` or a clone of it.
+    ///
+    /// This makes it possible to stop a thread that accepts connections
+    /// in a loop from another thread.
+    /// If another thread accepts the connection between `poll()` and `accept()`,
+    /// this waits for the next connection and doesn't notice shutdowns until then.
+    /// Use a nonblocking listener to avoid that when multiple threads accept from it.
+    pub fn accept_unless_shutdown(&self,  shutdown: &ShutdownHandle)
+    -> Result<Option<(UnixSeqpacketConn, UnixSocketAddr)>, io::Error> {
+        let mut fds = [
+            pollfd { fd: self.fd, events: POLLIN, revents: 0 },
+            pollfd { fd: shutdown.wakeup_fd(), events: POLLIN, revents: 0 },
+        ];
+        if shutdown.is_shutdown() {
+            return Ok(None);
+        }
+        self.poll_and_accept(&mut fds, None)
+    }
+    /// Waits until `fds[0]` (the listener) is readable and accepts a connection,
+    /// or returns `None` if the deadline passes or any of the other fds become readable.
+    ///
+    /// Retries if the listener is nonblocking and another thread accepted the connection.
+    fn poll_and_accept(&self,  fds: &mut[pollfd],  deadline: Option<Instant>)
+    -> Result<Option<(UnixSeqpacketConn, UnixSocketAddr)>, io::Error> {
+        loop {
+            let ready = poll_until(fds, deadline)?;
+            if ready == 0  ||  fds[1..].iter().any(|fd| fd.revents != 0 ) {
+                return Ok(None);
+            }
+            match self.accept_unix_addr() {
+                Err(ref e) if e.kind() == ErrorKind::WouldBlock => {}
+                result => return result.map(Some),
+            }
+        }
+    }
+
     /// Returns the value of the `SO_ERROR` option.
     ///
     /// This might never produce any errors for listeners. It is therefore
@@ -726,6 +1019,15 @@ impl UnixSeqpacketListener {
     pub fn set_nonblocking(&self,  nonblocking: bool) -> Result<(), io::Error> {
         set_nonblocking(self.fd, nonblocking)
     }
+
+    /// Enables nonblocking mode and converts this listener into the
+    /// nonblocking type, whose accepted connections are also nonblocking.
+    ///
+    /// The socket is closed if enabling nonblocking mode fails.
+    pub fn into_nonblocking(self) -> Result<NonblockingUnixSeqpacketListener, io::Error> {
+        set_nonblocking(self.fd, true)?;
+        Ok(NonblockingUnixSeqpacketListener { fd: self.into_raw_fd() })
+    }
 }
 
 
@@ -759,6 +1061,12 @@ impl UnixSeqpacketListener {
 /// uds = { version = "x.y", features=["mio_08"] }
 /// ```
 ///
+/// For mio version 1:
+///
+/// ```toml
+/// uds = { version = "x.y", features=["mio_1"] }
+/// ```
+///
 /// # Examples
 ///
 /// Sending or receiving when it would block a normal socket:
@@ -821,11 +1129,24 @@ impl_mio_if_enabled!{NonblockingUnixSeqpacketConn}
 impl NonblockingUnixSeqpacketConn {
     /// Connects to an unix seqpacket server listening at `path`.
     ///
-    /// This is a wrapper around [`connect_unix_addr()`](#method.connect_unix_addr)
-    /// for convenience and compatibility with std.
+    /// Unlike [`connect_unix_addr()`](#method.connect_unix_addr), this also
+    /// supports paths longer than
+    /// [`UnixSocketAddr::max_path_len()`](struct.UnixSocketAddr.html#method.max_path_len)
+    /// by connecting relative to the parent directory.
     pub fn connect<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
-        let addr = UnixSocketAddr::from_path(&path)?;
-        Self::connect_unix_addr(&addr)
+        let socket = Socket::new(SOCK_SEQPACKET, true)?;
+        set_path_addr(socket.as_raw_fd(), SetAddr::PEER, path.as_ref())?;
+        Ok(NonblockingUnixSeqpacketConn { fd: socket.into_raw_fd() })
+    }
+    /// Connects to an unix seqpacket server listening at `name`,
+    /// relative to the open directory `dirfd`.
+    ///
+    /// This avoids races with renames of the directory, like `connectat()`.
+    /// Absolute paths ignore `dirfd`.
+    pub fn connect_at<P: AsRef<Path>>(dirfd: RawFd,  name: P) -> Result<Self, io::Error> {
+        let socket = Socket::new(SOCK_SEQPACKET, true)?;
+        set_addr_at(socket.as_raw_fd(), SetAddr::PEER, dirfd, name.as_ref())?;
+        Ok(NonblockingUnixSeqpacketConn { fd: socket.into_raw_fd() })
     }
     /// Connects to an unix seqpacket server listening at `addr`.
     pub fn connect_unix_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error> {
@@ -841,6 +1162,15 @@ impl NonblockingUnixSeqpacketConn {
         set_unix_addr(socket.as_raw_fd(), SetAddr::PEER, to)?;
         Ok(NonblockingUnixSeqpacketConn { fd: socket.into_raw_fd() })
     }
+    /// Binds to an unused abstract address before connecting to a listening
+    /// seqpacket socket, and returns the address it got.
+    ///
+    /// See [`UnixSocketAddr::unique_abstract()`](struct.UnixSocketAddr.html#method.unique_abstract)
+    /// for how the address is chosen. This only works on Linux and Android.
+    pub fn connect_autobound(to: &UnixSocketAddr)
+    -> Result<(Self, UnixSocketAddr), io::Error> {
+        bind_unique(|from| Self::connect_from_to_unix_addr(from, to) )
+    }
 
     /// Creates a pair of nonblocking unix-domain seqpacket conneections connected to each other.
     ///
@@ -960,6 +1290,37 @@ impl NonblockingUnixSeqpacketConn {
         recv_ancillary(self.fd, None, MSG_PEEK, buffers, &mut[])
             .map(|(bytes, ancillary)| (bytes, ancillary.message_truncated()) )
     }
+    /// Starts peeking incrementally at incoming packets, without removing them.
+    ///
+    /// See [`UnixSeqpacketConn::peek_cursor()`](../struct.UnixSeqpacketConn.html#method.peek_cursor)
+    /// for details.
+    pub fn peek_cursor(&self) -> Result<PeekCursor<'_>, io::Error> {
+        PeekCursor::new(self.fd)
+    }
+
+    /// Returns the number of bytes in packets waiting to be received.
+    ///
+    /// See [`UnixSeqpacketConn::recv_queue_len()`](../struct.UnixSeqpacketConn.html#method.recv_queue_len)
+    /// for OS differences.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+    /// let (a, b) = uds::nonblocking::UnixSeqpacketConn::pair().unwrap();
+    /// a.send(b"hello").unwrap();
+    /// assert_eq!(b.recv_queue_len().unwrap(), 5);
+    /// ```
+    pub fn recv_queue_len(&self) -> Result<usize, io::Error> {
+        recv_queue_len(self.fd)
+    }
+    /// Returns the number of bytes sent but not yet received by the peer.
+    ///
+    /// See [`UnixSeqpacketConn::send_queue_len()`](../struct.UnixSeqpacketConn.html#method.send_queue_len)
+    /// for what the value represents on different OSes.
+    pub fn send_queue_len(&self) -> Result<usize, io::Error> {
+        send_queue_len(self.fd)
+    }
 
     /// Returns the value of the `SO_ERROR` option.
     ///
@@ -1006,6 +1367,25 @@ impl NonblockingUnixSeqpacketConn {
         Ok(NonblockingUnixSeqpacketConn { fd: cloned.into_raw_fd() })
     }
 
+    /// Disables nonblocking mode and converts this connection into the
+    /// blocking type, so that it can be handed to a worker thread.
+    ///
+    /// The socket is closed if disabling nonblocking mode fails.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(not(target_vendor="apple"), doc="```")]
+    #[cfg_attr(target_vendor="apple", doc="```no_run")]
+    /// let (a, b) = uds::nonblocking::UnixSeqpacketConn::pair().unwrap();
+    /// let a = a.into_blocking().unwrap();
+    /// std::thread::spawn(move || b.send(b"wait for me").unwrap() );
+    /// assert_eq!(a.recv(&mut[0; 20]).unwrap(), 11);
+    /// ```
+    pub fn into_blocking(self) -> Result<UnixSeqpacketConn, io::Error> {
+        set_nonblocking(self.fd, false)?;
+        Ok(UnixSeqpacketConn { fd: self.into_raw_fd() })
+    }
+
     /// Shuts down the read, write, or both halves of this connection.
     pub fn shutdown(&self, how: Shutdown) -> io::Result<()> {
         let how = match how {
@@ -1095,9 +1475,31 @@ impl_mio_if_enabled!{NonblockingUnixSeqpacketListener}
 
 impl NonblockingUnixSeqpacketListener {
     /// Creates a socket that listens for seqpacket connections on the specified socket file.
+    ///
+    /// Paths longer than
+    /// [`UnixSocketAddr::max_path_len()`](struct.UnixSocketAddr.html#method.max_path_len)
+    /// are bound relative to their parent directory.
+    /// [`local_unix_addr()`](#method.local_unix_addr) can't represent those,
+    /// so connect to the listener with `path`.
     pub fn bind<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
-        let addr = UnixSocketAddr::from_path(&path)?;
-        Self::bind_unix_addr(&addr)
+        let socket = Socket::new(SOCK_SEQPACKET, true)?;
+        set_path_addr(socket.as_raw_fd(), SetAddr::LOCAL, path.as_ref())?;
+        socket.start_listening()?;
+        Ok(NonblockingUnixSeqpacketListener { fd: socket.into_raw_fd() })
+    }
+    /// Creates a socket that listens for seqpacket connections on the
+    /// socket file `name`, relative to the open directory `dirfd`.
+    ///
+    /// This avoids races with renames of the directory, like `bindat()`.
+    /// Absolute paths ignore `dirfd`.
+    /// Returns the path of the socket file at the time it was created,
+    /// as [`local_unix_addr()`](#method.local_unix_addr) doesn't contain it on Linux.
+    pub fn bind_at<P: AsRef<Path>>(dirfd: RawFd,  name: P) -> Result<(Self, PathBuf), io::Error> {
+        let path = path_at(dirfd, name.as_ref())?;
+        let socket = Socket::new(SOCK_SEQPACKET, true)?;
+        set_addr_at(socket.as_raw_fd(), SetAddr::LOCAL, dirfd, name.as_ref())?;
+        socket.start_listening()?;
+        Ok((NonblockingUnixSeqpacketListener { fd: socket.into_raw_fd() }, path))
     }
     /// Creates a socket that listens for seqpacket connections on the specified address.
     pub fn bind_unix_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error> {
@@ -1106,6 +1508,23 @@ impl NonblockingUnixSeqpacketListener {
         socket.start_listening()?;
         Ok(NonblockingUnixSeqpacketListener { fd: socket.into_raw_fd() })
     }
+    /// Creates a socket that listens for seqpacket connections on an unused
+    /// abstract address, and returns the address.
+    ///
+    /// See [`UnixSocketAddr::unique_abstract()`](struct.UnixSocketAddr.html#method.unique_abstract)
+    /// for how the address is chosen. This only works on Linux and Android.
+    pub fn autobind() -> Result<(Self, UnixSocketAddr), io::Error> {
+        bind_unique(Self::bind_unix_addr)
+    }
+    /// Creates a socket that listens for seqpacket connections on a temporary address.
+    ///
+    /// This uses an abstract address where supported, and a socket file in
+    /// a private temporary directory elsewhere.
+    /// The returned [`TempBound`](struct.TempBound.html) derefs to the listener,
+    /// and removes the file after closing it.
+    pub fn bind_temp() -> Result<TempBound<Self>, io::Error> {
+        bind_temp(Self::bind_unix_addr)
+    }
 
     /// Returns the address this listener was bound to.
     pub fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
@@ -1163,4 +1582,13 @@ impl NonblockingUnixSeqpacketListener {
         // nonblockingness is shared and therefore inherited
         Ok(NonblockingUnixSeqpacketListener { fd: cloned.into_raw_fd() })
     }
+
+    /// Disables nonblocking mode and converts this listener into the
+    /// blocking type, whose accepted connections are also blocking.
+    ///
+    /// The socket is closed if disabling nonblocking mode fails.
+    pub fn into_blocking(self) -> Result<UnixSeqpacketListener, io::Error> {
+        set_nonblocking(self.fd, false)?;
+        Ok(UnixSeqpacketListener { fd: self.into_raw_fd() })
+    }
 }
@@ -0,0 +1,99 @@
+use std::fmt::{self, Debug};
+use std::io::{self, ErrorKind};
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use libc::{SOCK_STREAM, c_void, send};
+
+use crate::helpers::*;
+
+struct Wakeup {
+    shut_down: AtomicBool,
+    // never read from; it stays readable once something has been written
+    waiting: Socket,
+    waker: Socket,
+}
+
+/// Wakes up threads blocked in
+/// [`UnixSeqpacketListener::accept_unless_shutdown()`](struct.UnixSeqpacketListener.html#method.accept_unless_shutdown)
+/// from another thread.
+///
+/// Clones share the same state, so give one clone to the thread that
+/// accepts connections and keep another for stopping it.
+/// Once [`shutdown()`](#method.shutdown) has been called,
+/// all current and future waits will return immediately.
+///
+/// This is implemented with a socket pair, which works on all OSes
+/// unlike shutting down the listener itself.
+///
+/// # Examples
+///
+#[cfg_attr(not(target_vendor="apple"), doc="```")]
+#[cfg_attr(target_vendor="apple", doc="```no_run")]
+/// use uds::{ShutdownHandle, UnixSeqpacketListener};
+///
+/// let listener = UnixSeqpacketListener::bind_temp().unwrap();
+/// let handle = ShutdownHandle::new().unwrap();
+/// let for_thread = handle.clone();
+/// let server = std::thread::spawn(move || {
+///     while let Some((conn, _addr)) = listener.accept_unless_shutdown(&for_thread).unwrap() {
+///         /* handle the connection */
+///     }
+/// });
+/// handle.shutdown().unwrap();
+/// server.join().unwrap();
+/// ```
+#[derive(Clone)]
+pub struct ShutdownHandle {
+    inner: Arc<Wakeup>,
+}
+
+impl Debug for ShutdownHandle {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_struct("ShutdownHandle")
+            .field("shut_down", &self.is_shutdown())
+            .finish()
+    }
+}
+
+impl ShutdownHandle {
+    /// Creates a handle that hasn't been shut down yet.
+    pub fn new() -> Result<Self, io::Error> {
+        let (waiting, waker) = Socket::pair(SOCK_STREAM, true)?;
+        let inner = Wakeup { shut_down: AtomicBool::new(false), waiting, waker };
+        Ok(ShutdownHandle { inner: Arc::new(inner) })
+    }
+
+    /// Wakes up all threads waiting on this handle, and makes future waits
+    /// return immediately.
+    ///
+    /// Calling this more than once has no additional effect.
+    /// If waking up the waiting threads fails, the handle isn't shut down,
+    /// and calling this again retries.
+    pub fn shutdown(&self) -> Result<(), io::Error> {
+        if self.is_shutdown() {
+            return Ok(());
+        }
+        let byte = &0u8 as *const u8 as *const c_void;
+        let fd = self.inner.waker.as_raw_fd();
+        match cvt_r!(unsafe { send(fd, byte, 1, MSG_NOSIGNAL) }) {
+            Ok(_) => {}
+            // a full buffer means a previous call already made it readable
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => {}
+            Err(e) => return Err(e),
+        }
+        self.inner.shut_down.store(true, Ordering::SeqCst);
+        Ok(())
+    }
+
+    /// Returns whether [`shutdown()`](#method.shutdown) has been called.
+    pub fn is_shutdown(&self) -> bool {
+        self.inner.shut_down.load(Ordering::SeqCst)
+    }
+
+    /// A file descriptor that becomes readable when shut down.
+    pub(crate) fn wakeup_fd(&self) -> RawFd {
+        self.inner.waiting.as_raw_fd()
+    }
+}
@@ -0,0 +1,186 @@
+use std::fs::{self, DirBuilder};
+use std::io::{self, ErrorKind};
+use std::ops::{Deref, DerefMut};
+use std::os::unix::fs::DirBuilderExt;
+use std::os::unix::io::{AsRawFd, RawFd};
+use std::path::{Path, PathBuf};
+
+use crate::addr::{UnixSocketAddr, unique_suffix};
+use crate::helpers::bind_unique;
+
+/// A temporary socket address that is cleaned up when dropped.
+///
+/// Where abstract addresses are supported (Linux and Android) this is
+/// an unused abstract address, and dropping it does nothing.
+/// Elsewhere it is a path to a file in a newly created temporary directory
+/// that only the current user has access to (mode `0700`),
+/// and both the socket file and the directory are removed on drop.
+///
+/// Use [`UnixListenerExt::bind_temp()`](trait.UnixListenerExt.html#method.bind_temp),
+/// [`UnixDatagramExt::bind_temp()`](trait.UnixDatagramExt.html#method.bind_temp)
+/// or [`UnixSeqpacketListener::bind_temp()`](struct.UnixSeqpacketListener.html#method.bind_temp)
+/// to create a socket bound to one, which returns them together as a
+/// [`TempBound`](struct.TempBound.html).
+/// If binding yourself, keep this around for as long as the socket is in use.
+///
+/// # Examples
+///
+/// ```
+/// use std::os::unix::net::{UnixListener, UnixStream};
+/// use uds::{TempSocketPath, UnixListenerExt, UnixStreamExt};
+///
+/// let temp = TempSocketPath::new().unwrap();
+/// let listener = UnixListener::bind_unix_addr(temp.addr()).unwrap();
+/// let _client = UnixStream::connect_to_unix_addr(temp.addr()).unwrap();
+/// let (_server, _) = listener.accept().unwrap();
+/// // remove the file (if there is one) after the listener is closed
+/// drop(listener);
+/// drop(temp);
+/// ```
+#[derive(Debug)]
+#[must_use]
+pub struct TempSocketPath {
+    addr: UnixSocketAddr,
+    dir: Option<PathBuf>,
+}
+
+impl TempSocketPath {
+    /// Picks an unused abstract address if supported,
+    /// and otherwise creates a private temporary directory.
+    pub fn new() -> Result<Self, io::Error> {
+        if UnixSocketAddr::has_abstract_addresses() {
+            let addr = UnixSocketAddr::unique_abstract("uds")?;
+            Ok(TempSocketPath { addr, dir: None })
+        } else {
+            Self::new_path()
+        }
+    }
+
+    /// Creates a private temporary directory and returns a path
+    /// for a socket file in it, even if abstract addresses are supported.
+    ///
+    /// The directory is created in `std::env::temp_dir()`.
+    pub fn new_path() -> Result<Self, io::Error> {
+        let mut attempts = 0;
+        let dir = loop {
+            let dir = std::env::temp_dir().join(format!("uds{}", unique_suffix()));
+            match DirBuilder::new().mode(0o700).create(&dir) {
+                Ok(()) => break dir,
+                Err(ref e) if e.kind() == ErrorKind::AlreadyExists && attempts < 10 => {
+                    attempts += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        };
+        match UnixSocketAddr::from_path(&dir.join("socket")) {
+            Ok(addr) => Ok(TempSocketPath { addr, dir: Some(dir) }),
+            Err(e) => {
+                let _ = fs::remove_dir(&dir);
+                Err(e)
+            }
+        }
+    }
+
+    /// Returns the address to bind to, and that clients should connect to.
+    pub fn addr(&self) -> &UnixSocketAddr {
+        &self.addr
+    }
+
+    /// Returns the temporary directory, if one was created.
+    pub fn directory(&self) -> Option<&Path> {
+        self.dir.as_deref()
+    }
+}
+
+impl Drop for TempSocketPath {
+    fn drop(&mut self) {
+        if let Some(dir) = &self.dir {
+            // ignore errors - the socket might never have been bound
+            // and there is nowhere to return them
+            if let Some(path) = self.addr.as_pathname() {
+                let _ = fs::remove_file(path);
+            }
+            let _ = fs::remove_dir(dir);
+        }
+    }
+}
+
+/// A socket bound to a [`TempSocketPath`](struct.TempSocketPath.html),
+/// which is cleaned up after the socket is closed.
+///
+/// This derefs to the socket, so it can be used like one.
+/// Use [`into_parts()`](#method.into_parts) to take the socket out of it.
+///
+/// # Examples
+///
+/// ```
+/// use std::os::unix::net::{UnixListener, UnixStream};
+/// use uds::{UnixListenerExt, UnixStreamExt};
+///
+/// let listener = UnixListener::bind_temp().unwrap();
+/// let _client = UnixStream::connect_to_unix_addr(listener.addr()).unwrap();
+/// let (_server, _) = listener.accept().unwrap();
+/// ```
+#[derive(Debug)]
+pub struct TempBound<S> {
+    // declared first to be dropped first
+    socket: S,
+    temp: TempSocketPath,
+}
+
+impl<S> TempBound<S> {
+    /// Combines a socket with the temporary address it is bound to.
+    pub fn new(socket: S,  temp: TempSocketPath) -> Self {
+        TempBound { socket, temp }
+    }
+
+    /// Returns the address the socket is bound to.
+    pub fn addr(&self) -> &UnixSocketAddr {
+        self.temp.addr()
+    }
+
+    /// Returns the temporary address.
+    pub fn temp_path(&self) -> &TempSocketPath {
+        &self.temp
+    }
+
+    /// Separates the socket from its temporary address.
+    ///
+    /// Dropping the `TempSocketPath` removes any socket file,
+    /// so keep it for as long as the socket.
+    pub fn into_parts(self) -> (S, TempSocketPath) {
+        (self.socket, self.temp)
+    }
+}
+
+impl<S> Deref for TempBound<S> {
+    type Target = S;
+    fn deref(&self) -> &S {
+        &self.socket
+    }
+}
+
+impl<S> DerefMut for TempBound<S> {
+    fn deref_mut(&mut self) -> &mut S {
+        &mut self.socket
+    }
+}
+
+impl<S: AsRawFd> AsRawFd for TempBound<S> {
+    fn as_raw_fd(&self) -> RawFd {
+        self.socket.as_raw_fd()
+    }
+}
+
+/// Binds with `bind` to an unused abstract address if supported,
+/// and to a file in a new temporary directory otherwise.
+pub(crate) fn bind_temp<T, F>(mut bind: F) -> Result<TempBound<T>, io::Error>
+where F: FnMut(&UnixSocketAddr) -> Result<T, io::Error> {
+    if UnixSocketAddr::has_abstract_addresses() {
+        let (bound, addr) = bind_unique(bind)?;
+        Ok(TempBound::new(bound, TempSocketPath { addr, dir: None }))
+    } else {
+        let temp = TempSocketPath::new_path()?;
+        Ok(TempBound::new(bind(temp.addr())?, temp))
+    }
+}
@@ -0,0 +1,128 @@
+use crate::framed::{Frame, FrameReader, FrameWriter};
+use crate::UnixStreamExt;
+use futures::{future::poll_fn, ready, Stream};
+use mio::{event::Evented, unix::EventedFd, Poll as MioPoll, PollOpt, Ready, Token};
+use std::fmt::{self, Debug};
+use std::io::{self, ErrorKind};
+use std::os::unix::io::{AsRawFd, OwnedFd, RawFd};
+use std::os::unix::net::UnixStream;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio_02::io::PollEvented;
+
+struct EventedStream(UnixStream);
+
+impl Evented for EventedStream {
+    fn register(&self,  poll: &MioPoll,  token: Token,  interest: Ready,  opts: PollOpt)
+    -> Result<(), io::Error> {
+        EventedFd(&self.0.as_raw_fd()).register(poll, token, interest, opts)
+    }
+    fn reregister(&self,  poll: &MioPoll,  token: Token,  interest: Ready,  opts: PollOpt)
+    -> Result<(), io::Error> {
+        EventedFd(&self.0.as_raw_fd()).reregister(poll, token, interest, opts)
+    }
+    fn deregister(&self,  poll: &MioPoll) -> Result<(), io::Error> {
+        EventedFd(&self.0.as_raw_fd()).deregister(poll)
+    }
+}
+
+/// Length-delimited frames with attached file descriptors over a stream socket,
+/// for tokio.
+///
+/// See [`uds::framed`](../framed/index.html) for the format.
+/// Partially sent or received frames are kept when a future is dropped,
+/// and completed by the next call.
+///
+/// Also implements `Stream` for receiving frames, which ends when the peer
+/// closes the connection between frames.
+pub struct FramedStream {
+    io: PollEvented<EventedStream>,
+    writer: FrameWriter,
+    reader: FrameReader,
+}
+
+impl Debug for FramedStream {
+    fn fmt(&self,  fmtr: &mut fmt::Formatter) -> fmt::Result {
+        fmtr.debug_tuple("FramedStream").field(&self.io.get_ref().0).finish()
+    }
+}
+
+impl FramedStream {
+    /// Wraps a connected stream socket, making it nonblocking and
+    /// registering it with the default event loop.
+    pub fn from_std(stream: UnixStream) -> Result<Self, io::Error> {
+        stream.set_nonblocking(true)?;
+        let io = PollEvented::new(EventedStream(stream))?;
+        Ok(FramedStream { io, writer: FrameWriter::default(), reader: FrameReader::default() })
+    }
+
+    /// Creates a pair of connected framed streams.
+    pub fn pair() -> Result<(Self, Self), io::Error> {
+        let (a, b) = UnixStream::pair()?;
+        Ok((Self::from_std(a)?, Self::from_std(b)?))
+    }
+
+    /// Deregisters the socket and returns it, still in nonblocking mode.
+    ///
+    /// Any partially sent or received frame is discarded.
+    pub fn into_std(self) -> Result<UnixStream, io::Error> {
+        self.io.into_inner().map(|evented| evented.0 )
+    }
+
+    /// Sends a frame, with `fds` attached to it.
+    pub async fn send_frame(&mut self,  frame: &[u8],  fds: &[RawFd]) -> io::Result<()> {
+        if self.writer.is_partial() {
+            poll_fn(|cx| self.poll_write_frame(cx) ).await?;
+        }
+        self.writer.start(frame, fds)?;
+        poll_fn(|cx| self.poll_write_frame(cx) ).await
+    }
+
+    /// Receives a frame and the file descriptors attached to it.
+    ///
+    /// Returns an error of kind `ErrorKind::UnexpectedEof` if the peer has
+    /// closed the connection.
+    pub async fn recv_frame(&mut self) -> io::Result<(Vec<u8>, Vec<OwnedFd>)> {
+        match poll_fn(|cx| self.poll_read_frame(cx) ).await? {
+            Some(received) => Ok(received),
+            None => Err(io::Error::new(ErrorKind::UnexpectedEof, "stream closed")),
+        }
+    }
+
+    fn poll_write_frame(&mut self,  cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        ready!(self.io.poll_write_ready(cx))?;
+        let stream = &self.io.get_ref().0;
+        match self.writer.write(|bytes, fds| stream.send_fds(bytes, fds) ) {
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                self.io.clear_write_ready(cx)?;
+                Poll::Pending
+            }
+            x => Poll::Ready(x),
+        }
+    }
+
+    fn poll_read_frame(&mut self,  cx: &mut Context<'_>) -> Poll<io::Result<Option<Frame>>> {
+        ready!(self.io.poll_read_ready(cx, Ready::readable()))?;
+        let stream = &self.io.get_ref().0;
+        match self.reader.read(|buf, fd_buf| stream.recv_fds(buf, fd_buf) ) {
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                self.io.clear_read_ready(cx, Ready::readable())?;
+                Poll::Pending
+            }
+            x => Poll::Ready(x),
+        }
+    }
+}
+
+impl Stream for FramedStream {
+    type Item = io::Result<(Vec<u8>, Vec<OwnedFd>)>;
+    fn poll_next(mut self: Pin<&mut Self>,  cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.poll_read_frame(cx).map(Result::transpose)
+    }
+}
+
+impl AsRawFd for FramedStream {
+    fn as_raw_fd(&self) -> RawFd {
+        self.io.get_ref().0.as_raw_fd()
+    }
+}
@@ -2,3 +2,5 @@
 
 mod seqpacket;
 pub use seqpacket::*;
+mod framed;
+pub use framed::FramedStream;
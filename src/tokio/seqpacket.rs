@@ -1,4 +1,4 @@
-use crate::{nonblocking, UnixSocketAddr, ConnCredentials};
+use crate::{nonblocking, UnixSocketAddr, ConnCredentials, TempBound};
 use futures::{future::poll_fn, ready};
 use std::io::{self, ErrorKind, IoSlice, IoSliceMut};
 use std::net::Shutdown;
@@ -42,6 +42,17 @@ impl UnixSeqpacketConn {
         poll_fn(|cx| conn.io.poll_write_ready(cx)).await?;
         Ok(conn)
     }
+    /// Binds to an unused abstract address before connecting to a listening
+    /// seqpacket socket, and returns the address it got.
+    ///
+    /// This only works on Linux and Android.
+    pub async fn connect_autobound(to: &UnixSocketAddr) -> io::Result<(Self, UnixSocketAddr)> {
+        let (conn, addr) = nonblocking::UnixSeqpacketConn::connect_autobound(to)?;
+        let conn = Self::from_nonblocking(conn)?;
+
+        poll_fn(|cx| conn.io.poll_write_ready(cx)).await?;
+        Ok((conn, addr))
+    }
 
     /// Creates an unnamed pair of connected sockets.
     ///
@@ -116,6 +127,18 @@ impl UnixSeqpacketConn {
     pub fn take_error(&self) -> Result<Option<io::Error>, io::Error> {
         self.io.get_ref().take_error()
     }
+
+    /// Returns the number of bytes in packets waiting to be received.
+    pub fn recv_queue_len(&self) -> Result<usize, io::Error> {
+        self.io.get_ref().recv_queue_len()
+    }
+    /// Returns the number of bytes sent but not yet received by the peer.
+    ///
+    /// See [`uds::UnixSeqpacketConn::send_queue_len()`](crate::UnixSeqpacketConn::send_queue_len)
+    /// for what the value represents on different OSes.
+    pub fn send_queue_len(&self) -> Result<usize, io::Error> {
+        self.io.get_ref().send_queue_len()
+    }
 }
 
 impl UnixSeqpacketConn {
@@ -240,6 +263,22 @@ impl UnixSeqpacketListener {
             Err(e) => Err(e),
         }
     }
+    /// Creates a socket that listens for seqpacket connections on an unused
+    /// abstract address, and returns the address.
+    ///
+    /// This only works on Linux and Android.
+    pub fn autobind() -> Result<(Self, UnixSocketAddr), io::Error> {
+        let (listener, addr) = nonblocking::UnixSeqpacketListener::autobind()?;
+        Ok((Self::from_nonblocking(listener)?, addr))
+    }
+    /// Creates a socket that listens for seqpacket connections on a temporary address.
+    ///
+    /// The returned `TempBound` derefs to the listener,
+    /// and removes the socket file, if there is one, after closing it.
+    pub fn bind_temp() -> Result<TempBound<Self>, io::Error> {
+        let (listener, temp) = nonblocking::UnixSeqpacketListener::bind_temp()?.into_parts();
+        Ok(TempBound::new(Self::from_nonblocking(listener)?, temp))
+    }
 
     /// Creates a tokio-compatible listener from an existing nonblocking listener.
     pub fn from_nonblocking(listener: nonblocking::UnixSeqpacketListener)
@@ -1,13 +1,18 @@
 use std::os::unix::io::{RawFd, AsRawFd, FromRawFd, IntoRawFd};
-use std::os::unix::net::{UnixStream, UnixListener, UnixDatagram};
+use std::os::unix::net::{self, UnixStream, UnixListener, UnixDatagram};
+use std::convert::TryFrom;
 use std::io::{self, IoSlice, IoSliceMut, ErrorKind};
+use std::time::Duration;
+use std::path::{Path, PathBuf};
 
-use libc::{SOCK_STREAM, MSG_PEEK, c_void, recvfrom, sendto};
+use libc::{SOCK_STREAM, MSG_PEEK, POLLIN, POLLOUT, c_void, recvfrom, sendto};
 
 use crate::addr::UnixSocketAddr;
 use crate::helpers::*;
 use crate::ancillary::*;
 use crate::credentials::*;
+use crate::peek::PeekCursor;
+use crate::temp::{TempBound, bind_temp};
 
 /// Extension trait for `std::os::unix::net::UnixDatagram` and nonblocking equivalents.
 pub trait UnixStreamExt: AsRawFd + FromRawFd {
@@ -28,6 +33,45 @@ pub trait UnixStreamExt: AsRawFd + FromRawFd {
     fn connect_from_to_unix_addr(from: &UnixSocketAddr,  to: &UnixSocketAddr)
     -> Result<Self, io::Error> where Self: Sized;
 
+    /// Creates a connection to a listening socket at a `std` `SocketAddr`,
+    /// which can be abstract.
+    fn connect_to_std_addr(addr: &net::SocketAddr) -> Result<Self, io::Error> where Self: Sized {
+        Self::connect_to_unix_addr(&UnixSocketAddr::try_from(addr)?)
+    }
+    /// Creates a socket bound to a `std` `SocketAddr` and connects to a listening socket.
+    fn connect_from_to_std_addr(from: &net::SocketAddr,  to: &net::SocketAddr)
+    -> Result<Self, io::Error> where Self: Sized {
+        let from = UnixSocketAddr::try_from(from)?;
+        Self::connect_from_to_unix_addr(&from, &UnixSocketAddr::try_from(to)?)
+    }
+    /// Creates a socket bound to an unused abstract address,
+    /// connects it to a listening socket, and returns the address it got.
+    ///
+    /// See [`UnixSocketAddr::unique_abstract()`](struct.UnixSocketAddr.html#method.unique_abstract)
+    /// for how the address is chosen. This only works on Linux and Android.
+    fn connect_autobound(to: &UnixSocketAddr) -> Result<(Self, UnixSocketAddr), io::Error>
+    where Self: Sized {
+        bind_unique(|from| Self::connect_from_to_unix_addr(from, to) )
+    }
+
+    /// Creates a connection to a socket file, which can be longer than
+    /// [`UnixSocketAddr::max_path_len()`](struct.UnixSocketAddr.html#method.max_path_len).
+    ///
+    /// Long paths are connected to relative to their parent directory.
+    fn connect_to_path<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> where Self: Sized {
+        with_path_addr(path.as_ref(), Self::connect_to_unix_addr)
+    }
+
+    /// Creates a connection to the socket file `name`,
+    /// relative to the open directory `dirfd`.
+    ///
+    /// This avoids races with renames of the directory, like `connectat()`.
+    /// Absolute paths ignore `dirfd`.
+    fn connect_at<P: AsRef<Path>>(dirfd: RawFd,  name: P) -> Result<Self, io::Error>
+    where Self: Sized {
+        with_addr_at(dirfd, name.as_ref(), Self::connect_to_unix_addr)
+    }
+
     /// Sends file descriptors in addition to bytes.
     fn send_fds(&self,  bytes: &[u8],  fds: &[RawFd]) -> Result<usize, io::Error> {
         send_ancillary(self.as_raw_fd(), None, 0, &[IoSlice::new(bytes)], fds, None)
@@ -38,6 +82,16 @@ pub trait UnixStreamExt: AsRawFd + FromRawFd {
             .map(|(bytes, _, fds)| (bytes, fds) )
     }
 
+    /// Starts peeking incrementally at the incoming data, without removing it.
+    ///
+    /// Successive peeks through the returned cursor continue where the
+    /// previous one stopped.
+    /// This is only supported on Linux and Android.
+    /// See [`PeekCursor`](struct.PeekCursor.html) for details.
+    fn peek_cursor(&self) -> Result<PeekCursor<'_>, io::Error> {
+        PeekCursor::new(self.as_raw_fd())
+    }
+
     /// Returns the credentials of the process that created the other end of this stream.
     fn initial_peer_credentials(&self) -> Result<ConnCredentials, io::Error> {
         peer_credentials(self.as_raw_fd())
@@ -53,8 +107,67 @@ pub trait UnixStreamExt: AsRawFd + FromRawFd {
     fn initial_peer_selinux_context(&self,  buffer: &mut[u8]) -> Result<usize, io::Error> {
         selinux_context(self.as_raw_fd(), buffer)
     }
+
+    /// Returns the number of bytes that can be read without blocking.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use std::os::unix::net::UnixStream;
+    /// # use std::io::Write;
+    /// use uds::UnixStreamExt;
+    ///
+    /// let (mut a, b) = UnixStream::pair().unwrap();
+    /// a.write_all(b"hello").unwrap();
+    /// assert_eq!(b.recv_queue_len().unwrap(), 5);
+    /// ```
+    fn recv_queue_len(&self) -> Result<usize, io::Error> {
+        recv_queue_len(self.as_raw_fd())
+    }
+    /// Returns the number of bytes written but not yet read by the peer.
+    ///
+    /// This uses `SIOCOUTQ` on Linux, `FIONWRITE` on FreeBSD and NetBSD,
+    /// and `SO_NWRITE` on macOS, and returns an error on other OSes.  
+    /// On Linux the value includes some kernel bookkeeping overhead,
+    /// while on FreeBSD and NetBSD written data is usually moved directly
+    /// to the peer's receive buffer, making it zero.
+    fn send_queue_len(&self) -> Result<usize, io::Error> {
+        send_queue_len(self.as_raw_fd())
+    }
+
+    /// Blocks until there is something to receive, or `timeout` elapses.
+    ///
+    /// Returns `false` if the timeout elapsed. `None` waits indefinitely.  
+    /// A closed connection also counts as readable.  
+    /// This uses `poll()`, and doesn't need the socket to be nonblocking.
+    fn wait_readable(&self,  timeout: Option<Duration>) -> Result<bool, io::Error> {
+        wait_ready(self.as_raw_fd(), POLLIN, timeout)
+    }
+
+    /// Blocks until there is room to send without blocking, or `timeout` elapses.
+    ///
+    /// Returns `false` if the timeout elapsed. `None` waits indefinitely.  
+    /// This uses `poll()`, and doesn't need the socket to be nonblocking.
+    fn wait_writable(&self,  timeout: Option<Duration>) -> Result<bool, io::Error> {
+        wait_ready(self.as_raw_fd(), POLLOUT, timeout)
+    }
 }
 
+/// Implements `connect_to_path()` and `connect_at()` with `set_path_addr()` and `set_addr_at()`,
+/// which use `connectat()` on FreeBSD instead of the working directory.
+macro_rules! impl_connect_path_methods {($nonblocking:expr) => {
+    fn connect_to_path<P: AsRef<Path>>(path: P) -> Result<Self, io::Error> {
+        let socket = Socket::new(SOCK_STREAM, $nonblocking)?;
+        set_path_addr(socket.as_raw_fd(), SetAddr::PEER, path.as_ref())?;
+        Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+    }
+    fn connect_at<P: AsRef<Path>>(dirfd: RawFd,  name: P) -> Result<Self, io::Error> {
+        let socket = Socket::new(SOCK_STREAM, $nonblocking)?;
+        set_addr_at(socket.as_raw_fd(), SetAddr::PEER, dirfd, name.as_ref())?;
+        Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+    }
+}}
+
 impl UnixStreamExt for UnixStream {
     fn connect_to_unix_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error> {
         let socket = Socket::new(SOCK_STREAM, false)?;
@@ -68,6 +181,7 @@ impl UnixStreamExt for UnixStream {
         set_unix_addr(socket.as_raw_fd(), SetAddr::PEER, to)?;
         Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
     }
+    impl_connect_path_methods!{false}
 }
 
 #[cfg(feature="mio-uds")]
@@ -84,6 +198,7 @@ impl UnixStreamExt for mio_uds::UnixStream {
         set_unix_addr(socket.as_raw_fd(), SetAddr::PEER, to)?;
         Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
     }
+    impl_connect_path_methods!{true}
 }
 
 #[cfg(feature = "mio_07")]
@@ -100,6 +215,7 @@ impl UnixStreamExt for mio_07::net::UnixStream {
         set_unix_addr(socket.as_raw_fd(), SetAddr::PEER, to)?;
         Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
     }
+    impl_connect_path_methods!{true}
 }
 
 #[cfg(feature = "mio_08")]
@@ -116,6 +232,24 @@ impl UnixStreamExt for mio_08::net::UnixStream {
         set_unix_addr(socket.as_raw_fd(), SetAddr::PEER, to)?;
         Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
     }
+    impl_connect_path_methods!{true}
+}
+
+#[cfg(feature = "mio_1")]
+impl UnixStreamExt for mio_1::net::UnixStream {
+    fn connect_to_unix_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error> {
+        let socket = Socket::new(SOCK_STREAM, true)?;
+        set_unix_addr(socket.as_raw_fd(), SetAddr::PEER, addr)?;
+        Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+    }
+    fn connect_from_to_unix_addr(from: &UnixSocketAddr,  to: &UnixSocketAddr)
+    -> Result<Self, io::Error> {
+        let socket = Socket::new(SOCK_STREAM, true)?;
+        set_unix_addr(socket.as_raw_fd(), SetAddr::LOCAL, from)?;
+        set_unix_addr(socket.as_raw_fd(), SetAddr::PEER, to)?;
+        Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+    }
+    impl_connect_path_methods!{true}
 }
 
 /// Extension trait for using [`UnixSocketAddr`](struct.UnixSocketAddr.html) with `UnixListener` types.
@@ -125,6 +259,67 @@ pub trait UnixListenerExt: AsRawFd + FromRawFd {
 
     /// Creates a socket bound to a `UnixSocketAddr` and starts listening on it.
     fn bind_unix_addr(on: &UnixSocketAddr) -> Result<Self, io::Error> where Self: Sized;
+    /// Creates a socket bound to a `std` `SocketAddr`, which can be abstract,
+    /// and starts listening on it.
+    fn bind_std_addr(on: &net::SocketAddr) -> Result<Self, io::Error> where Self: Sized {
+        Self::bind_unix_addr(&UnixSocketAddr::try_from(on)?)
+    }
+    /// Creates a socket bound to an unused abstract address, starts listening
+    /// on it and returns the address.
+    ///
+    /// See [`UnixSocketAddr::unique_abstract()`](struct.UnixSocketAddr.html#method.unique_abstract)
+    /// for how the address is chosen. This only works on Linux and Android.
+    ///
+    /// # Examples
+    ///
+    #[cfg_attr(any(target_os="linux", target_os="android"), doc="```")]
+    #[cfg_attr(not(any(target_os="linux", target_os="android")), doc="```no_run")]
+    /// # use std::os::unix::net::{UnixListener, UnixStream};
+    /// # use uds::{UnixListenerExt, UnixStreamExt};
+    /// let (listener, addr) = UnixListener::autobind().unwrap();
+    /// let _client = UnixStream::connect_to_unix_addr(&addr).unwrap();
+    /// let _server = listener.accept().unwrap();
+    /// ```
+    fn autobind() -> Result<(Self, UnixSocketAddr), io::Error> where Self: Sized {
+        bind_unique(Self::bind_unix_addr)
+    }
+    /// Creates a socket bound to a temporary address and starts listening on it.
+    ///
+    /// This uses an abstract address where supported, and a socket file in
+    /// a private temporary directory elsewhere.
+    /// The returned [`TempBound`](struct.TempBound.html) derefs to the listener,
+    /// and removes the file after closing it.
+    fn bind_temp() -> Result<TempBound<Self>, io::Error> where Self: Sized {
+        bind_temp(Self::bind_unix_addr)
+    }
+
+    /// Creates a socket bound to a path and starts listening on it,
+    /// and returns the path it can be connected to at.
+    ///
+    /// Paths longer than
+    /// [`UnixSocketAddr::max_path_len()`](struct.UnixSocketAddr.html#method.max_path_len)
+    /// are bound relative to their parent directory.
+    /// [`local_unix_addr()`](#method.local_unix_addr) can't represent those,
+    /// so keep the returned path for connecting to the listener.
+    fn bind_path<P: AsRef<Path>>(path: P) -> Result<(Self, PathBuf), io::Error> where Self: Sized {
+        let path = path.as_ref();
+        let listener = with_path_addr(path, Self::bind_unix_addr)?;
+        Ok((listener, path.to_path_buf()))
+    }
+
+    /// Creates a socket bound to the socket file `name`, relative to the
+    /// open directory `dirfd`, and starts listening on it.
+    ///
+    /// This avoids races with renames of the directory, like `bindat()`.
+    /// Absolute paths ignore `dirfd`.
+    /// Returns the path of the socket file at the time it was created,
+    /// as [`local_unix_addr()`](#method.local_unix_addr) doesn't contain it on Linux.
+    fn bind_at<P: AsRef<Path>>(dirfd: RawFd,  name: P) -> Result<(Self, PathBuf), io::Error>
+    where Self: Sized {
+        let path = path_at(dirfd, name.as_ref())?;
+        let listener = with_addr_at(dirfd, name.as_ref(), Self::bind_unix_addr)?;
+        Ok((listener, path))
+    }
 
     /// Returns the address this socket is listening on.
     fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
@@ -134,8 +329,36 @@ pub trait UnixListenerExt: AsRawFd + FromRawFd {
     /// Accepts a connection and returns the client's address as
     /// an `uds::UnixSocketAddr`.
     fn accept_unix_addr(&self) -> Result<(Self::Conn, UnixSocketAddr), io::Error>;
+
+    /// Blocks until there is a connection to accept, or `timeout` elapses.
+    ///
+    /// Returns `false` if the timeout elapsed. `None` waits indefinitely.  
+    /// This uses `poll()`, and doesn't need the socket to be nonblocking.
+    fn wait_readable(&self,  timeout: Option<Duration>) -> Result<bool, io::Error> {
+        wait_ready(self.as_raw_fd(), POLLIN, timeout)
+    }
 }
 
+/// Implements `bind_path()` and `bind_at()` with `set_path_addr()` and `set_addr_at()`,
+/// which use `bindat()` on FreeBSD instead of the working directory.
+macro_rules! impl_bind_path_methods {($nonblocking:expr) => {
+    fn bind_path<P: AsRef<Path>>(path: P) -> Result<(Self, PathBuf), io::Error> {
+        let socket = Socket::new(SOCK_STREAM, $nonblocking)?;
+        set_path_addr(socket.as_raw_fd(), SetAddr::LOCAL, path.as_ref())?;
+        socket.start_listening()?;
+        let listener = unsafe { Self::from_raw_fd(socket.into_raw_fd()) };
+        Ok((listener, path.as_ref().to_path_buf()))
+    }
+    fn bind_at<P: AsRef<Path>>(dirfd: RawFd,  name: P) -> Result<(Self, PathBuf), io::Error> {
+        let path = path_at(dirfd, name.as_ref())?;
+        let socket = Socket::new(SOCK_STREAM, $nonblocking)?;
+        set_addr_at(socket.as_raw_fd(), SetAddr::LOCAL, dirfd, name.as_ref())?;
+        socket.start_listening()?;
+        let listener = unsafe { Self::from_raw_fd(socket.into_raw_fd()) };
+        Ok((listener, path))
+    }
+}}
+
 impl UnixListenerExt for UnixListener {
     type Conn = UnixStream;
 
@@ -145,6 +368,7 @@ impl UnixListenerExt for UnixListener {
         socket.start_listening()?;
         Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
     }
+    impl_bind_path_methods!{false}
 
     fn accept_unix_addr(&self) -> Result<(Self::Conn, UnixSocketAddr), io::Error> {
         let (socket, addr) = Socket::accept_from(self.as_raw_fd(), false)?;
@@ -163,6 +387,7 @@ impl UnixListenerExt for mio_uds::UnixListener {
         socket.start_listening()?;
         Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
     }
+    impl_bind_path_methods!{true}
 
     fn accept_unix_addr(&self) -> Result<(Self::Conn, UnixSocketAddr), io::Error> {
         let (socket, addr) = Socket::accept_from(self.as_raw_fd(), true)?;
@@ -181,6 +406,7 @@ impl UnixListenerExt for mio_07::net::UnixListener {
         socket.start_listening()?;
         Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
     }
+    impl_bind_path_methods!{true}
 
     fn accept_unix_addr(&self) -> Result<(Self::Conn, UnixSocketAddr), io::Error> {
         let (socket, addr) = Socket::accept_from(self.as_raw_fd(), true)?;
@@ -199,6 +425,26 @@ impl UnixListenerExt for mio_08::net::UnixListener {
         socket.start_listening()?;
         Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
     }
+    impl_bind_path_methods!{true}
+
+    fn accept_unix_addr(&self) -> Result<(Self::Conn, UnixSocketAddr), io::Error> {
+        let (socket, addr) = Socket::accept_from(self.as_raw_fd(), true)?;
+        let conn = unsafe { Self::Conn::from_raw_fd(socket.into_raw_fd()) };
+        Ok((conn, addr))
+    }
+}
+
+#[cfg(feature = "mio_1")]
+impl UnixListenerExt for mio_1::net::UnixListener {
+    type Conn = mio_1::net::UnixStream;
+
+    fn bind_unix_addr(on: &UnixSocketAddr) -> Result<Self, io::Error> {
+        let socket = Socket::new(SOCK_STREAM, true)?;
+        set_unix_addr(socket.as_raw_fd(), SetAddr::LOCAL, on)?;
+        socket.start_listening()?;
+        Ok(unsafe { Self::from_raw_fd(socket.into_raw_fd()) })
+    }
+    impl_bind_path_methods!{true}
 
     fn accept_unix_addr(&self) -> Result<(Self::Conn, UnixSocketAddr), io::Error> {
         let (socket, addr) = Socket::accept_from(self.as_raw_fd(), true)?;
@@ -241,6 +487,19 @@ pub trait UnixDatagramExt: AsRawFd + FromRawFd {
     /// # }
     /// ```
     fn bind_unix_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error> where Self: Sized;
+    /// Creates a socket bound to a `std` `SocketAddr`, which can be abstract.
+    fn bind_std_addr(addr: &net::SocketAddr) -> Result<Self, io::Error> where Self: Sized {
+        Self::bind_unix_addr(&UnixSocketAddr::try_from(addr)?)
+    }
+    /// Creates a socket bound to a temporary address.
+    ///
+    /// This uses an abstract address where supported, and a socket file in
+    /// a private temporary directory elsewhere.
+    /// The returned [`TempBound`](struct.TempBound.html) derefs to the socket,
+    /// and removes the file after closing it.
+    fn bind_temp() -> Result<TempBound<Self>, io::Error> where Self: Sized {
+        bind_temp(Self::bind_unix_addr)
+    }
 
     /// Returns the address of this socket, as a type that fully supports abstract addresses.
     fn local_unix_addr(&self) -> Result<UnixSocketAddr, io::Error> {
@@ -259,6 +518,57 @@ pub trait UnixDatagramExt: AsRawFd + FromRawFd {
     fn connect_to_unix_addr(&self,  addr: &UnixSocketAddr) -> Result<(), io::Error> {
         set_unix_addr(self.as_raw_fd(), SetAddr::PEER, addr)
     }
+    /// Binds the socket to a `std` `SocketAddr`, which can be abstract.
+    fn bind_to_std_addr(&self,  addr: &net::SocketAddr) -> Result<(), io::Error> {
+        self.bind_to_unix_addr(&UnixSocketAddr::try_from(addr)?)
+    }
+    /// Binds the socket to an unused abstract address, and returns the address.
+    ///
+    /// See [`UnixSocketAddr::unique_abstract()`](struct.UnixSocketAddr.html#method.unique_abstract)
+    /// for how the address is chosen. This only works on Linux and Android.
+    fn autobind(&self) -> Result<UnixSocketAddr, io::Error> {
+        bind_unique(|addr| self.bind_to_unix_addr(addr) ).map(|((), addr)| addr )
+    }
+    /// Connects the socket to a `std` `SocketAddr`, which can be abstract.
+    fn connect_to_std_addr(&self,  addr: &net::SocketAddr) -> Result<(), io::Error> {
+        self.connect_to_unix_addr(&UnixSocketAddr::try_from(addr)?)
+    }
+
+    /// Creates a socket file at `path`, and returns the path the socket can
+    /// be reached at.
+    ///
+    /// Paths longer than
+    /// [`UnixSocketAddr::max_path_len()`](struct.UnixSocketAddr.html#method.max_path_len)
+    /// are bound relative to their parent directory.
+    /// [`local_unix_addr()`](#method.local_unix_addr) can't represent those,
+    /// and neither can the address peers see, so keep the returned path instead.
+    fn bind_to_path<P: AsRef<Path>>(&self,  path: P) -> Result<PathBuf, io::Error> {
+        set_path_addr(self.as_raw_fd(), SetAddr::LOCAL, path.as_ref())?;
+        Ok(path.as_ref().to_path_buf())
+    }
+    /// Connects the socket to a socket file, which can be longer than
+    /// [`UnixSocketAddr::max_path_len()`](struct.UnixSocketAddr.html#method.max_path_len).
+    fn connect_to_path<P: AsRef<Path>>(&self,  path: P) -> Result<(), io::Error> {
+        set_path_addr(self.as_raw_fd(), SetAddr::PEER, path.as_ref())
+    }
+
+    /// Creates the socket file `name` relative to the open directory `dirfd`,
+    /// like `bindat()`, and returns the path of the file at the time it was created.
+    ///
+    /// Absolute paths ignore `dirfd`.
+    /// [`local_unix_addr()`](#method.local_unix_addr) doesn't contain the path on Linux.
+    fn bind_at<P: AsRef<Path>>(&self,  dirfd: RawFd,  name: P) -> Result<PathBuf, io::Error> {
+        let path = path_at(dirfd, name.as_ref())?;
+        set_addr_at(self.as_raw_fd(), SetAddr::LOCAL, dirfd, name.as_ref())?;
+        Ok(path)
+    }
+    /// Connects the socket to the socket file `name`,
+    /// relative to the open directory `dirfd`, like `connectat()`.
+    ///
+    /// Absolute paths ignore `dirfd`.
+    fn connect_at<P: AsRef<Path>>(&self,  dirfd: RawFd,  name: P) -> Result<(), io::Error> {
+        set_addr_at(self.as_raw_fd(), SetAddr::PEER, dirfd, name.as_ref())
+    }
 
     /// Sends to the specified address, using an address type that
     /// supports abstract addresses.
@@ -316,6 +626,17 @@ pub trait UnixDatagramExt: AsRawFd + FromRawFd {
     -> Result<usize, io::Error> {
         send_ancillary(self.as_raw_fd(), Some(addr), 0, datagram, &[], None)
     }
+    /// Sends to a `std` `SocketAddr`, which can be abstract.
+    fn send_to_std_addr(&self,  datagram: &[u8],  addr: &net::SocketAddr)
+    -> Result<usize, io::Error> {
+        self.send_to_unix_addr(datagram, &UnixSocketAddr::try_from(addr)?)
+    }
+    /// Sends a datagram created from multiple segments to a `std` `SocketAddr`,
+    /// which can be abstract.
+    fn send_vectored_to_std_addr(&self,  datagram: &[IoSlice],  addr: &net::SocketAddr)
+    -> Result<usize, io::Error> {
+        self.send_vectored_to_unix_addr(datagram, &UnixSocketAddr::try_from(addr)?)
+    }
     /// Receives from any peer, storing its address in a type that exposes
     /// abstract addresses.
     ///
@@ -500,6 +821,11 @@ pub trait UnixDatagramExt: AsRawFd + FromRawFd {
     -> Result<usize, io::Error> {
         send_ancillary(self.as_raw_fd(), Some(addr), 0, &[IoSlice::new(datagram)], fds, None)
     }
+    /// Sends file descriptors along with the datagram to a `std` `SocketAddr`.
+    fn send_fds_to_std_addr(&self,  datagram: &[u8],  fds: &[RawFd],  addr: &net::SocketAddr)
+    -> Result<usize, io::Error> {
+        self.send_fds_to(datagram, fds, &UnixSocketAddr::try_from(addr)?)
+    }
     /// Sends file descriptors along with the datagram, on a connected socket.
     fn send_fds(&self,  datagram: &[u8],  fds: &[RawFd]) -> Result<usize, io::Error> {
         send_ancillary(self.as_raw_fd(), None, 0, &[IoSlice::new(datagram)], fds, None)
@@ -551,6 +877,35 @@ pub trait UnixDatagramExt: AsRawFd + FromRawFd {
     fn initial_pair_selinux_context(&self,  buffer: &mut[u8]) -> Result<usize, io::Error> {
         selinux_context(self.as_raw_fd(), buffer)
     }
+
+    /// Returns the size of the next datagram waiting to be received on Linux,
+    /// and the combined size of all waiting datagrams on most other OSes.
+    fn recv_queue_len(&self) -> Result<usize, io::Error> {
+        recv_queue_len(self.as_raw_fd())
+    }
+    /// Returns the number of bytes sent but not yet received by the peer.
+    ///
+    /// See [`UnixStreamExt::send_queue_len()`](trait.UnixStreamExt.html#method.send_queue_len)
+    /// for what the value represents on different OSes.
+    fn send_queue_len(&self) -> Result<usize, io::Error> {
+        send_queue_len(self.as_raw_fd())
+    }
+
+    /// Blocks until there is a datagram to receive, or `timeout` elapses.
+    ///
+    /// Returns `false` if the timeout elapsed. `None` waits indefinitely.  
+    /// This uses `poll()`, and doesn't need the socket to be nonblocking.
+    fn wait_readable(&self,  timeout: Option<Duration>) -> Result<bool, io::Error> {
+        wait_ready(self.as_raw_fd(), POLLIN, timeout)
+    }
+
+    /// Blocks until there is room to send without blocking, or `timeout` elapses.
+    ///
+    /// Returns `false` if the timeout elapsed. `None` waits indefinitely.  
+    /// This uses `poll()`, and doesn't need the socket to be nonblocking.
+    fn wait_writable(&self,  timeout: Option<Duration>) -> Result<bool, io::Error> {
+        wait_ready(self.as_raw_fd(), POLLOUT, timeout)
+    }
 }
 
 impl UnixDatagramExt for UnixDatagram {
@@ -603,3 +958,16 @@ impl UnixDatagramExt for mio_08::net::UnixDatagram {
         }
     }
 }
+
+#[cfg(feature = "mio_1")]
+impl UnixDatagramExt for mio_1::net::UnixDatagram {
+    fn bind_unix_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error> {
+        match mio_1::net::UnixDatagram::unbound() {
+            Ok(socket) => match socket.bind_to_unix_addr(addr) {
+                Ok(()) => Ok(socket),
+                Err(e) => Err(e),
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
@@ -6,6 +6,7 @@ use std::io::ErrorKind::*;
 use std::io::{IoSlice, IoSliceMut};
 use std::fs::remove_file;
 use std::path::Path;
+use std::os::unix::ffi::OsStrExt;
 use std::mem::size_of;
 
 use libc::{sockaddr, sockaddr_un, socklen_t};
@@ -339,3 +340,194 @@ fn datagram_peek_vectored() {
     std::fs::remove_file("datagram_server.sock").unwrap();
     let _ = std::fs::remove_file("datagram_client.sock");
 }
+
+#[test]
+fn display_and_parse_paths() {
+    let paths: &[(&[u8], &str)] = &[
+        (b"plain.sock", "plain.sock"),
+        (b"/var/run/with space", "/var/run/with space"),
+        (b"new\nline", "new\\nline"),
+        (b"back\\slash", "back\\\\slash"),
+        (b"@not abstract", "\\@not abstract"),
+        (b"unnamed", "\\x75nnamed"),
+        (b"bell\x07", "bell\\x07"),
+        (b"invalid\xff\xc3", "invalid\\xff\\xc3"),
+        ("ünïcode".as_bytes(), "ünïcode"),
+    ];
+    for &(path, displayed) in paths {
+        let addr = UnixSocketAddr::from_path(std::ffi::OsStr::from_bytes(path)).unwrap();
+        assert_eq!(addr.to_string(), displayed);
+        let parsed = displayed.parse::<UnixSocketAddr>()
+            .unwrap_or_else(|e| panic!("parse {:?}: {}", displayed, e));
+        assert_eq!(parsed, addr, "{:?} doesn't round-trip", displayed);
+    }
+}
+
+#[test]
+fn display_and_parse_unnamed() {
+    let unnamed = UnixSocketAddr::new_unspecified();
+    assert_eq!(unnamed.to_string(), "unnamed");
+    assert_eq!("unnamed".parse::<UnixSocketAddr>().unwrap(), unnamed);
+    assert_eq!("".parse::<UnixSocketAddr>().unwrap(), unnamed);
+}
+
+#[cfg(any(target_os="linux", target_os="android"))]
+#[test]
+fn display_and_parse_abstract() {
+    let names: &[(&[u8], &str)] = &[
+        (b"", "@"),
+        (b"name", "@name"),
+        (b"@", "@@"),
+        (b"\0nul\0", "@\\0nul\\0"),
+        (b"\r\n\t", "@\\r\\n\\t"),
+        (b"\x1b[m\x7f", "@\\x1b[m\\x7f"),
+        (b"\xfe\xff", "@\\xfe\\xff"),
+        ("\u{85}".as_bytes(), "@\\xc2\\x85"),
+    ];
+    for &(name, displayed) in names {
+        let addr = UnixSocketAddr::from_abstract(name).unwrap();
+        assert_eq!(addr.to_string(), displayed);
+        let parsed = displayed.parse::<UnixSocketAddr>()
+            .unwrap_or_else(|e| panic!("parse {:?}: {}", displayed, e));
+        assert_eq!(parsed, addr, "{:?} doesn't round-trip", displayed);
+    }
+    assert_eq!("\0name".parse::<UnixSocketAddr>().unwrap(), UnixSocketAddr::new("@name").unwrap());
+}
+
+#[test]
+fn parse_invalid_escapes() {
+    for invalid in &["trailing\\", "\\q", "@\\x", "@\\x1", "\\xg0", "@\\u{20}"] {
+        let err = invalid.parse::<UnixSocketAddr>().unwrap_err();
+        assert_eq!(err.kind(), InvalidInput, "{:?}", invalid);
+    }
+    // NUL is not allowed in paths
+    assert!("nul\\0path".parse::<UnixSocketAddr>().is_err());
+}
+
+#[test]
+fn std_conversions() {
+    use std::convert::TryFrom;
+    use std::os::unix::net::SocketAddr;
+
+    let path = UnixSocketAddr::from_path("/run/std conversion.sock").unwrap();
+    let std_path = SocketAddr::try_from(&path).unwrap();
+    assert_eq!(std_path.as_pathname(), path.as_pathname());
+    assert_eq!(UnixSocketAddr::try_from(std_path).unwrap(), path);
+
+    let unnamed = UnixSocketAddr::new_unspecified();
+    let std_unnamed = SocketAddr::try_from(unnamed).unwrap();
+    assert!(std_unnamed.is_unnamed());
+    assert_eq!(UnixSocketAddr::from_std(std_unnamed), Some(unnamed));
+
+    let (a, _) = UnixStream::pair().unwrap();
+    assert_eq!(UnixSocketAddr::try_from(a.local_addr().unwrap()).unwrap(), unnamed);
+}
+
+#[cfg(any(target_os="linux", target_os="android"))]
+#[test]
+fn std_abstract_conversions() {
+    use std::convert::TryFrom;
+    use std::os::unix::net::SocketAddr;
+
+    let addr = UnixSocketAddr::from_abstract(b"std\0conversion").unwrap();
+    let std_addr = SocketAddr::try_from(&addr).unwrap();
+    assert_eq!(UnixSocketAddr::from_std(std_addr.clone()), Some(addr));
+
+    let listener = UnixListener::bind_std_addr(&std_addr).expect("bind to std abstract address");
+    assert_eq!(listener.local_unix_addr().unwrap(), addr);
+    let _client = UnixStream::connect_to_std_addr(&std_addr).unwrap();
+    let (_, from) = listener.accept().unwrap();
+    assert_eq!(UnixSocketAddr::try_from(&from).unwrap(), UnixSocketAddr::new_unspecified());
+
+    let receiver = UnixDatagram::unbound().unwrap();
+    let receiver_addr = SocketAddr::try_from(
+        UnixSocketAddr::from_abstract("std datagram conversion").unwrap()
+    ).unwrap();
+    receiver.bind_to_std_addr(&receiver_addr).unwrap();
+    let sender = UnixDatagram::unbound().unwrap();
+    sender.send_to_std_addr(b"abstract", &receiver_addr).unwrap();
+    assert_eq!(receiver.recv(&mut[0; 10]).unwrap(), 8);
+}
+
+#[cfg(any(target_os="linux", target_os="android"))]
+#[test]
+fn unique_abstract_and_autobind() {
+    let a = UnixSocketAddr::unique_abstract(b"uds-test").unwrap();
+    let b = UnixSocketAddr::unique_abstract(b"uds-test").unwrap();
+    assert_ne!(a, b);
+    assert!(a.as_abstract().unwrap().starts_with(b"uds-test-"));
+    let too_long = vec![b'x'; UnixSocketAddr::max_abstract_len()];
+    assert_eq!(UnixSocketAddr::unique_abstract(&too_long).unwrap_err().kind(), InvalidInput);
+
+    let (listener, addr) = UnixListener::autobind().unwrap();
+    assert_eq!(listener.local_unix_addr().unwrap(), addr);
+    let (_client, client_addr) = UnixStream::connect_autobound(&addr).unwrap();
+    assert_eq!(listener.accept_unix_addr().unwrap().1, client_addr);
+
+    let socket = UnixDatagram::unbound().unwrap();
+    let socket_addr = socket.autobind().unwrap();
+    assert_eq!(socket.local_unix_addr().unwrap(), socket_addr);
+    assert!(socket.autobind().is_err(), "already bound");
+}
+
+#[cfg(not(any(target_os="linux", target_os="android")))]
+#[test]
+fn autobind_requires_abstract_addresses() {
+    assert!(UnixSocketAddr::unique_abstract("uds-test").is_err());
+    assert!(UnixListener::autobind().is_err());
+}
+
+#[test]
+fn normalized() {
+    let with_nuls = UnixSocketAddr::from_raw_bytes(b"sock\0\0\0").unwrap();
+    let without_nul = UnixSocketAddr::from_raw_bytes(b"sock").unwrap();
+    assert_eq!(with_nuls.normalized().as_raw_bytes(), without_nul.normalized().as_raw_bytes());
+    assert_eq!(with_nuls.normalized(), UnixSocketAddr::from_path("sock").unwrap());
+
+    let messy = UnixSocketAddr::from_path("./a//b/./c/").unwrap();
+    assert_eq!(messy.normalized().as_pathname(), Some(Path::new("a/b/c")));
+    let parent = UnixSocketAddr::from_path("a/../b").unwrap();
+    assert_eq!(parent.normalized(), parent);
+    let current = UnixSocketAddr::from_path("./").unwrap();
+    assert_eq!(current.normalized().as_pathname(), Some(Path::new(".")));
+
+    let unnamed = UnixSocketAddr::from_raw_bytes(&[0; 3]).unwrap();
+    if !UnixSocketAddr::has_abstract_addresses() {
+        assert_eq!(unnamed.normalized().as_raw_bytes(), UnixSocketAddr::new_unspecified().as_raw_bytes());
+    }
+    assert!(UnixSocketAddr::new_unspecified().normalized().is_unnamed());
+}
+
+#[test]
+fn same_endpoint() {
+    let absolute = UnixSocketAddr::from_path("/run/app/app.sock").unwrap();
+    let relative = UnixSocketAddr::from_path("app.sock").unwrap();
+    let dotted = UnixSocketAddr::from_path("./app.sock").unwrap();
+    assert!(relative.is_same_endpoint(&dotted, None));
+    assert!(!relative.is_same_endpoint(&absolute, None));
+    assert!(relative.is_same_endpoint(&absolute, Some(Path::new("/run/app"))));
+    assert!(!relative.is_same_endpoint(&absolute, Some(Path::new("/run"))));
+    assert!(absolute.is_same_endpoint(&absolute, Some(Path::new("/elsewhere"))));
+    let unnamed = UnixSocketAddr::new_unspecified();
+    assert!(unnamed.is_same_endpoint(&unnamed, None));
+    assert!(!unnamed.is_same_endpoint(&relative, None));
+}
+
+#[test]
+fn same_socket_file() {
+    let dir = std::env::temp_dir().join(format!("uds same_socket_file {}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir(&dir).unwrap();
+    let _socket = UnixDatagram::bind(dir.join("original.sock")).unwrap();
+    std::fs::hard_link(dir.join("original.sock"), dir.join("link.sock")).unwrap();
+    let _other = UnixDatagram::bind(dir.join("other.sock")).unwrap();
+
+    let original = UnixSocketAddr::from_path("original.sock").unwrap();
+    let link = UnixSocketAddr::from_path("link.sock").unwrap();
+    let other = UnixSocketAddr::from_path("other.sock").unwrap();
+    assert!(!original.is_same_endpoint(&link, Some(&dir)));
+    assert!(original.is_same_socket_file(&link, Some(&dir)).unwrap());
+    assert!(!original.is_same_socket_file(&other, Some(&dir)).unwrap());
+    assert_eq!(original.is_same_socket_file(&other, None).unwrap_err().kind(), NotFound);
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,125 @@
+#![cfg(all(feature="async-io", not(target_vendor="apple")))]
+
+use std::io::{IoSlice, IoSliceMut, Read, Write};
+use std::net::Shutdown;
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::os::unix::net::UnixStream;
+
+use async_io::block_on;
+use futures::{future::join, StreamExt};
+
+use uds::async_io::{UnixSeqpacketConn, UnixSeqpacketListener};
+
+#[test]
+fn listener_stream() {
+    block_on(async {
+        let mut listener = UnixSeqpacketListener::bind_temp().unwrap();
+        let listener_addr = *listener.addr();
+        assert_eq!(listener.local_addr().unwrap(), listener_addr);
+
+        let server = async {
+            for i in 1..=3u8 {
+                let (conn, addr) = listener.next().await.unwrap().unwrap();
+                assert!(addr.is_unnamed());
+                conn.send(&[b'h', b'i', b'0' + i]).await.unwrap();
+            }
+        };
+        let clients = async {
+            for i in 1..=3u8 {
+                let conn = UnixSeqpacketConn::connect_addr(&listener_addr).await.unwrap();
+                let mut buf = [0u8; 3];
+                assert_eq!(conn.recv(&mut buf).await.unwrap(), 3);
+                assert_eq!(buf, [b'h', b'i', b'0' + i]);
+            }
+        };
+        join(server, clients).await;
+    });
+}
+
+#[test]
+fn accept_and_nonblocking_roundtrip() {
+    block_on(async {
+        let listener = UnixSeqpacketListener::bind_temp().unwrap();
+        let path = listener.addr().as_pathname().map(|path| path.to_owned() );
+        let connect = async {
+            match path {
+                Some(path) => UnixSeqpacketConn::connect(path).await,
+                None => UnixSeqpacketConn::connect_addr(listener.addr()).await,
+            }
+        };
+        let (accepted, client) = join(listener.accept(), connect).await;
+        let (server, _) = accepted.unwrap();
+        let client = client.unwrap();
+        assert_eq!(&client.peer_addr().unwrap(), listener.addr());
+
+        let client = client.into_nonblocking().unwrap();
+        client.send(b"sync").unwrap();
+        let client = UnixSeqpacketConn::from_nonblocking(client).unwrap();
+        assert_eq!(server.recv(&mut[0; 8]).await.unwrap(), 4);
+        assert!(client.take_error().unwrap().is_none());
+    });
+}
+
+#[test]
+fn vectored_and_peek() {
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    block_on(async {
+        a.send_vectored(&[IoSlice::new(b"hi"), IoSlice::new(b"there")]).await.unwrap();
+
+        let mut buf = [0; 10];
+        assert_eq!(b.peek(&mut buf).await.unwrap(), 7);
+        assert_eq!(&buf, b"hithere\0\0\0");
+        assert_eq!(b.recv_queue_len().unwrap(), 7);
+
+        let mut bufs = [[0; 3]; 3];
+        let mut slices = bufs.iter_mut()
+            .map(|array| IoSliceMut::new(array) )
+            .collect::<Vec<IoSliceMut>>();
+        assert_eq!(b.recv_vectored(&mut slices).await.unwrap(), 7);
+        assert_eq!(bufs, [*b"hit", *b"her", *b"e\0\0"]);
+    });
+}
+
+#[test]
+fn recv_waits_for_send() {
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    block_on(async {
+        let mut buf = [0; 10];
+        let (received, sent) = join(b.recv(&mut buf), async { a.send(b"later").await }).await;
+        assert_eq!(sent.unwrap(), 5);
+        assert_eq!(received.unwrap(), 5);
+        assert_eq!(&buf[..5], b"later");
+    });
+}
+
+#[cfg_attr(not(any(target_os="illumos", target_os="solaris")), test)]
+#[cfg_attr(any(target_os="illumos", target_os="solaris"), allow(unused))]
+fn fd_passing() {
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    let (mut to_pass, mut to_test) = UnixStream::pair().unwrap();
+    block_on(async {
+        a.send_fds(b"a stream", &[to_pass.as_raw_fd()]).await.unwrap();
+        to_pass.write_all(b"once").unwrap();
+
+        let mut byte_buf = [0; 8];
+        let mut fd_buf = [-1; 2];
+        let (bytes, truncated, fds) = b.recv_fds(&mut byte_buf, &mut fd_buf).await.unwrap();
+        assert_eq!((bytes, truncated, fds), (8, false, 1));
+        assert_eq!(&byte_buf, b"a stream");
+
+        let mut received = unsafe { UnixStream::from_raw_fd(fd_buf[0]) };
+        received.write_all(b" and again").unwrap();
+        assert_eq!(to_test.read(&mut byte_buf).unwrap(), 8);
+        assert_eq!(&byte_buf, b"once and");
+    });
+}
+
+#[test]
+fn shutdown() {
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    block_on(async {
+        a.shutdown(Shutdown::Both).unwrap();
+        assert!(a.send(b"hi0").await.is_err());
+        assert_eq!(b.recv(&mut [0u8; 3]).await.unwrap(), 0);
+    });
+}
@@ -0,0 +1,94 @@
+use std::fs::{self, File};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::{UnixDatagram, UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+
+use uds::{UnixDatagramExt, UnixListenerExt, UnixStreamExt};
+
+fn temp_dir(test: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    dir.push(format!("uds bind_at {} {}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir(&dir).expect("create temporary directory");
+    dir
+}
+
+#[test]
+fn stream_survives_rename() {
+    let dir_path = temp_dir("stream");
+    let dir = File::open(&dir_path).unwrap();
+    let (listener, listener_path) = UnixListener::bind_at(dir.as_raw_fd(), "listener.socket")
+        .expect("bind relative to directory");
+    assert!(dir_path.join("listener.socket").exists());
+    assert_eq!(listener_path, fs::canonicalize(&dir_path).unwrap().join("listener.socket"));
+    let _client = UnixStream::connect_to_path(&listener_path).expect("connect to returned path");
+    listener.accept_unix_addr().unwrap();
+
+    let moved_path = dir_path.with_extension("moved");
+    let _ = fs::remove_dir_all(&moved_path);
+    fs::rename(&dir_path, &moved_path).unwrap();
+    let _client = UnixStream::connect_at(dir.as_raw_fd(), "listener.socket")
+        .expect("connect relative to renamed directory");
+    listener.accept_unix_addr().unwrap();
+    let _ = fs::remove_dir_all(moved_path);
+}
+
+#[test]
+fn datagram() {
+    let dir_path = temp_dir("datagram");
+    let dir = File::open(&dir_path).unwrap();
+    let a = UnixDatagram::unbound().unwrap();
+    let a_path = a.bind_at(dir.as_raw_fd(), "a.socket").unwrap();
+    assert_eq!(a_path, fs::canonicalize(&dir_path).unwrap().join("a.socket"));
+    let b = UnixDatagram::unbound().unwrap();
+    b.bind_at(dir.as_raw_fd(), "b.socket").unwrap();
+    b.connect_at(dir.as_raw_fd(), "a.socket").unwrap();
+    b.send(b"relative").unwrap();
+    assert_eq!(a.recv_from_unix_addr(&mut[0; 10]).unwrap().0, 8);
+    b.send_to(b"by path", &a_path).unwrap();
+    assert_eq!(a.recv_from_unix_addr(&mut[0; 10]).unwrap().0, 7);
+    let _ = fs::remove_dir_all(dir_path);
+}
+
+#[cfg(not(target_vendor="apple"))]
+#[test]
+fn seqpacket() {
+    use uds::{UnixSeqpacketConn, UnixSeqpacketListener};
+
+    let dir_path = temp_dir("seqpacket");
+    let dir = File::open(&dir_path).unwrap();
+    let (listener, listener_path)
+        = UnixSeqpacketListener::bind_at(dir.as_raw_fd(), "seqpacket.socket").unwrap();
+    let client = UnixSeqpacketConn::connect_at(dir.as_raw_fd(), "seqpacket.socket").unwrap();
+    let (server, _) = listener.accept_unix_addr().unwrap();
+    client.send(b"relative").unwrap();
+    assert_eq!(server.recv(&mut[0; 10]).unwrap(), 8);
+    let _client = UnixSeqpacketConn::connect(&listener_path).expect("connect to returned path");
+    listener.accept_unix_addr().unwrap();
+    let _ = fs::remove_dir_all(dir_path);
+}
+
+#[test]
+fn absolute_and_cwd() {
+    let dir_path = temp_dir("absolute");
+    let socket_path = dir_path.join("absolute.socket");
+    let (_listener, listener_path)
+        = UnixListener::bind_at(-1, &socket_path).expect("absolute path ignores dirfd");
+    assert_eq!(listener_path, socket_path);
+    let _client = UnixStream::connect_at(libc::AT_FDCWD, &socket_path).unwrap();
+    let _ = fs::remove_dir_all(dir_path);
+
+    let cwd_path = "bind_at cwd.socket";
+    let _ = fs::remove_file(cwd_path);
+    let (_listener, listener_path) = UnixListener::bind_at(libc::AT_FDCWD, cwd_path).unwrap();
+    assert_eq!(listener_path, Path::new(cwd_path));
+    let _client = UnixStream::connect(cwd_path).unwrap();
+    let _ = fs::remove_file(cwd_path);
+}
+
+#[test]
+fn not_a_directory() {
+    let file = File::open("Cargo.toml").unwrap();
+    assert!(UnixStream::connect_at(file.as_raw_fd(), "nothing.socket").is_err());
+    assert!(UnixStream::connect_at(file.as_raw_fd(), "").is_err());
+}
@@ -0,0 +1,42 @@
+use std::process::Command;
+
+use uds::UnixSocketAddr;
+
+#[test]
+fn probed() {
+    let capabilities = uds::capabilities();
+    assert_eq!(capabilities.max_path_len, UnixSocketAddr::max_path_len());
+    assert!(capabilities.peer_credentials);
+    #[cfg(not(any(target_os="illumos", target_os="solaris")))]
+    assert!(capabilities.fd_passing);
+    assert_eq!(capabilities.fd_passing, capabilities.max_fds_per_message > 0);
+    assert!(!capabilities.peer_pid  ||  capabilities.peer_credentials);
+}
+
+#[cfg(any(target_os="linux", target_os="android"))]
+#[test]
+fn linux() {
+    let capabilities = uds::capabilities();
+    assert!(capabilities.seqpacket);
+    assert!(capabilities.seqpacket_empty_packets);
+    assert!(capabilities.abstract_addresses);
+    assert!(capabilities.pair_credentials);
+    assert!(capabilities.peer_pid);
+    assert!(capabilities.scm_credentials);
+    assert_eq!(capabilities.max_fds_per_message, 253);
+    assert!(capabilities.fds_cloned_on_send);
+    assert!(capabilities.accept_timeout);
+}
+
+#[test]
+fn characteristics_json() {
+    let output = Command::new(env!("CARGO_BIN_EXE_characteristics"))
+        .arg("--json")
+        .output()
+        .expect("run characteristics");
+    assert!(output.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&output.stdout).expect("parse output as JSON");
+    assert_eq!(json["os"], std::env::consts::OS);
+    assert_eq!(json["max_path_len"], UnixSocketAddr::max_path_len() as u64);
+    assert_eq!(json["fd_passing"], uds::capabilities().fd_passing);
+}
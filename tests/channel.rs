@@ -0,0 +1,173 @@
+#![cfg(all(feature="channel", not(target_vendor="apple")))]
+
+use std::io::{ErrorKind, Read, Write};
+use std::os::unix::io::{AsRawFd, OwnedFd};
+use std::os::unix::net::UnixStream;
+
+use serde::{Deserialize, Serialize};
+#[cfg(feature="tokio")]
+use tokio_02 as tokio;
+
+use uds::channel::{self, Fd, Receiver};
+use uds::UnixSeqpacketConn;
+
+#[derive(Serialize, Deserialize, Debug)]
+struct Work {
+    name: String,
+    streams: Vec<Fd>,
+    data: Vec<u8>,
+}
+
+fn stream_fd() -> (UnixStream, Fd) {
+    let (a, b) = UnixStream::pair().unwrap();
+    (a, Fd::from(OwnedFd::from(b)))
+}
+
+fn assert_connected(mut ours: UnixStream,  theirs: Fd) {
+    let mut theirs = UnixStream::from(theirs.into_inner());
+    theirs.write_all(b"ping").unwrap();
+    let mut buf = [0; 4];
+    ours.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"ping");
+}
+
+#[test]
+fn fds_in_message() {
+    let (mut sender, mut receiver) = channel::pair::<Work>().unwrap();
+    let (a, a_fd) = stream_fd();
+    let (b, b_fd) = stream_fd();
+    let work = Work { name: "two streams".to_string(), streams: vec![a_fd, b_fd], data: Vec::new() };
+    sender.send(&work).unwrap();
+    sender.send(&Work { name: "none".to_string(), streams: Vec::new(), data: vec![1] }).unwrap();
+
+    let mut received = receiver.recv().unwrap();
+    assert_eq!(received.name, "two streams");
+    assert_eq!(received.streams.len(), 2);
+    assert_ne!(received.streams[0].as_raw_fd(), work.streams[0].as_raw_fd());
+    assert_connected(b, received.streams.pop().unwrap());
+    assert_connected(a, received.streams.pop().unwrap());
+
+    let received = receiver.recv().unwrap();
+    assert_eq!((received.name.as_str(), received.streams.len(), &received.data[..]), ("none", 0, &[1][..]));
+}
+
+#[test]
+fn fragmented() {
+    let (mut sender, mut receiver) = channel::pair::<Work>().unwrap();
+    let (stream, fd) = stream_fd();
+    let data = (0..100_000).map(|i| i as u8 ).collect::<Vec<u8>>();
+    let sending = std::thread::spawn(move || {
+        sender.send(&Work { name: "big".to_string(), streams: vec![fd], data }).unwrap();
+        sender.send(&Work { name: "after".to_string(), streams: Vec::new(), data: Vec::new() }).unwrap();
+    });
+
+    let mut received = receiver.recv().unwrap();
+    assert_eq!(received.data.len(), 100_000);
+    assert!(received.data.iter().enumerate().all(|(i, &byte)| byte == i as u8 ));
+    assert_connected(stream, received.streams.pop().unwrap());
+    assert_eq!(receiver.recv().unwrap().name, "after");
+    sending.join().unwrap();
+}
+
+#[test]
+fn partially_sent() {
+    let (a, b) = UnixSeqpacketConn::pair().unwrap();
+    a.set_nonblocking(true).unwrap();
+    let mut sender = channel::Sender::<Work>::new(a);
+    let big = Work { name: "big".to_string(), streams: Vec::new(), data: vec![0; 8_000_000] };
+    assert_eq!(sender.send(&big).unwrap_err().kind(), ErrorKind::WouldBlock);
+    let small = Work { name: "small".to_string(), streams: Vec::new(), data: Vec::new() };
+    let error = sender.send(&small).expect_err("send after partially sent message");
+    assert_eq!(error.kind(), ErrorKind::BrokenPipe);
+    drop(b);
+}
+
+#[test]
+fn closed() {
+    let (sender, mut receiver) = channel::pair::<String>().unwrap();
+    drop(sender);
+    assert_eq!(receiver.recv().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn truncated_packet() {
+    let (raw, conn) = UnixSeqpacketConn::pair().unwrap();
+    let mut receiver = Receiver::<String>::new(conn);
+    raw.send(&[b'u'; 20_000]).unwrap();
+    assert_eq!(receiver.recv().unwrap_err().kind(), ErrorKind::InvalidData);
+    raw.send(b"not a channel message").unwrap();
+    assert_eq!(receiver.recv().unwrap_err().kind(), ErrorKind::InvalidData);
+}
+
+#[test]
+fn missing_fds() {
+    let (raw, conn) = UnixSeqpacketConn::pair().unwrap();
+    let mut receiver = Receiver::<Vec<Fd>>::new(conn);
+    // header claiming two file descriptors, followed by a Vec with two indexes
+    let mut packet = b"udsC".to_vec();
+    packet.extend_from_slice(&16u32.to_le_bytes());
+    packet.extend_from_slice(&2u16.to_le_bytes());
+    packet.extend_from_slice(&2u64.to_le_bytes());
+    packet.extend_from_slice(&0u32.to_le_bytes());
+    packet.extend_from_slice(&1u32.to_le_bytes());
+    raw.send_fds(&packet, &[raw.as_raw_fd()]).unwrap();
+    assert_eq!(receiver.recv().unwrap_err().kind(), ErrorKind::InvalidData);
+
+    raw.send_fds(&packet, &[raw.as_raw_fd(), raw.as_raw_fd()]).unwrap();
+    assert_eq!(receiver.recv().unwrap().len(), 2);
+}
+
+#[test]
+fn fd_outside_channel() {
+    let (_stream, fd) = stream_fd();
+    assert!(serde_json::to_string(&fd).is_err());
+    assert!(serde_json::from_str::<Fd>("0").is_err());
+}
+
+#[cfg(feature="tokio")]
+#[tokio::test]
+async fn tokio_channel() {
+    let (mut sender, mut receiver) = channel::tokio::pair::<Work>().unwrap();
+    let (stream, fd) = stream_fd();
+    let data = vec![7; 50_000];
+    let sending = tokio::spawn(async move {
+        sender.send(&Work { name: "async".to_string(), streams: vec![fd], data }).await.unwrap();
+    });
+
+    let mut received = receiver.recv().await.unwrap();
+    assert_eq!(received.name, "async");
+    assert_eq!(received.data, vec![7; 50_000]);
+    assert_connected(stream, received.streams.pop().unwrap());
+    sending.await.unwrap();
+    assert_eq!(receiver.recv().await.unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
+
+#[cfg(feature="tokio")]
+#[tokio::test]
+async fn tokio_cancelled() {
+    use futures::FutureExt;
+
+    let (mut sender, mut receiver) = channel::tokio::pair::<Vec<u8>>().unwrap();
+    // let the reactor find out that the sockets are ready
+    sender.send(&vec![0]).await.unwrap();
+    assert_eq!(receiver.recv().await.unwrap(), vec![0]);
+
+    let big = vec![9; 2_000_000];
+    // sends as many packets as there is room for
+    assert!(sender.send(&big).now_or_never().is_none(), "message fits in the socket buffer");
+    // receives some of them
+    assert!(receiver.recv().now_or_never().is_none());
+
+    let sending = async {
+        sender.send(&vec![1, 2, 3]).await.unwrap();
+        sender
+    };
+    let receiving = async {
+        let first = receiver.recv().await.unwrap();
+        let second = receiver.recv().await.unwrap();
+        (first, second)
+    };
+    let (_sender, (first, second)) = futures::join!(sending, receiving);
+    assert!(first == big, "first message was corrupted");
+    assert_eq!(second, vec![1, 2, 3]);
+}
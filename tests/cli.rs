@@ -0,0 +1,126 @@
+use std::io::{Read, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::os::unix::net::{UnixListener, UnixStream};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::Duration;
+
+use uds::{ChildSocket, CommandSocketExt, UnixListenerExt, UnixSocketAddr, UnixStreamExt};
+
+fn uds() -> Command {
+    let mut command = Command::new(env!("CARGO_BIN_EXE_uds"));
+    command.stdin(Stdio::piped()).stdout(Stdio::piped()).stderr(Stdio::piped());
+    command
+}
+
+#[cfg(not(target_vendor="apple"))]
+#[test]
+fn seqpacket_to_stdio() {
+    use uds::UnixSeqpacketListener;
+
+    let listener = UnixSeqpacketListener::bind_temp().unwrap();
+    let mut child = uds().arg(format!("connect-seqpacket:{}", listener.addr())).spawn().unwrap();
+    let (conn, _) = listener.accept_unix_addr().unwrap();
+    child.stdin.take().unwrap().write_all(b"hello").unwrap();
+    let mut buf = [0; 10];
+    assert_eq!(conn.recv(&mut buf).unwrap(), 5);
+    assert_eq!(&buf[..5], b"hello");
+    // stdin was closed
+    assert_eq!(conn.recv(&mut buf).unwrap(), 0);
+    conn.send(b"world").unwrap();
+    drop(conn);
+
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert_eq!(output.stdout, b"world");
+}
+
+#[test]
+fn send_fd() {
+    let listener = UnixListener::bind_temp().unwrap();
+    let (mut ours, theirs) = UnixStream::pair().unwrap();
+    let mut command = uds();
+    command.args(["--send-fd", "3", &format!("connect-stream:{}", listener.addr())]);
+    command.pass_sockets(&[ChildSocket::new(&theirs, 3)]).unwrap();
+    let mut child = command.spawn().unwrap();
+    drop(theirs);
+    child.stdin.take().unwrap().write_all(b"x").unwrap();
+
+    let (conn, _) = listener.accept_unix_addr().unwrap();
+    let mut fd_buf = [-1; 2];
+    assert_eq!(conn.recv_fds(&mut[0; 10], &mut fd_buf).unwrap(), (1, 1));
+    let mut received = unsafe { UnixStream::from_raw_fd(fd_buf[0]) };
+    received.write_all(b"through fd").unwrap();
+    let mut buf = [0; 10];
+    ours.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"through fd");
+    drop(conn);
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn listen_prints_credentials_and_execs_with_fds() {
+    let addr = match UnixSocketAddr::unique_abstract("uds-cli") {
+        Ok(addr) => addr,
+        Err(_) => {
+            let dir = std::env::temp_dir().join(format!("uds-cli-{}", std::process::id()));
+            UnixSocketAddr::from_path(&dir).unwrap()
+        }
+    };
+    let child = uds()
+        .args(["--recv-fd-exec", "echo executed >&3", &format!("listen-stream:{}", addr)])
+        .spawn()
+        .unwrap();
+    let conn = loop {
+        match UnixStream::connect_to_unix_addr(&addr) {
+            Ok(conn) => break conn,
+            Err(_) => thread::sleep(Duration::from_millis(10)),
+        }
+    };
+    let (mut ours, theirs) = UnixStream::pair().unwrap();
+    conn.send_fds(b"fd", &[theirs.as_raw_fd()]).unwrap();
+    drop(theirs);
+    let mut output = String::new();
+    ours.read_to_string(&mut output).unwrap();
+    assert_eq!(output, "executed\n");
+
+    drop(conn);
+    let output = child.wait_with_output().unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(output.status.success(), "{}", stderr);
+    assert_eq!(output.stdout, b"fd");
+    assert!(stderr.contains("peer credentials"), "{}", stderr);
+    if let Some(path) = addr.as_pathname() {
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[test]
+fn waits_for_exec_commands() {
+    let listener = UnixListener::bind_temp().unwrap();
+    let mut child = uds()
+        .args(["--recv-fd-exec", "exec >/dev/null 2>&1; sleep 0.2; echo late >&3", &format!("connect-stream:{}", listener.addr())])
+        .spawn()
+        .unwrap();
+    let (conn, _) = listener.accept_unix_addr().unwrap();
+    let (mut ours, theirs) = UnixStream::pair().unwrap();
+    conn.send_fds(b"fd", &[theirs.as_raw_fd()]).unwrap();
+    drop(theirs);
+    drop(conn);
+    drop(child.stdin.take());
+    let output = child.wait_with_output().unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    // the command has exited and closed its copy of the fd
+    ours.set_nonblocking(true).unwrap();
+    let mut output = String::new();
+    ours.read_to_string(&mut output).unwrap();
+    assert_eq!(output, "late\n");
+}
+
+#[test]
+fn bad_arguments() {
+    for args in [&["connect-stream"][..], &["connect-raw:x"], &["--nope", "listen-stream:x"], &[]] {
+        let output = uds().args(args).output().unwrap();
+        assert_eq!(output.status.code(), Some(2), "{:?}", args);
+    }
+}
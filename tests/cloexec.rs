@@ -3,12 +3,17 @@ extern crate uds;
 use std::ffi::OsStr;
 use std::fs::remove_file;
 use std::os::unix::io::{RawFd, AsRawFd};
-use std::os::unix::net::{UnixListener, UnixStream};
+use std::io::ErrorKind;
+use std::os::unix::net::{UnixDatagram, UnixListener, UnixStream};
 use std::process::{Command, Stdio};
 
-use uds::{UnixSocketAddr, UnixListenerExt, UnixStreamExt};
+use uds::{ChildSocket, CommandSocketExt, UnixSocketAddr, UnixListenerExt, UnixStreamExt};
 
 fn is_cloexec(fd: RawFd) -> bool {
+    is_cloexec_in(fd, |_| {} )
+}
+
+fn is_cloexec_in(fd: RawFd,  configure: impl FnOnce(&mut Command)) -> bool {
     let mut exe = std::env::current_exe().expect("get directory of tests binary");
     exe.pop(); // pop tests binary
     if exe.file_name() == Some(OsStr::new("deps")) {// pop conditionally to future-proof
@@ -16,12 +21,13 @@ fn is_cloexec(fd: RawFd) -> bool {
     }
     exe.push("cloexec_tester");
     eprintln!("target exe: {:?}", exe);
-    let output = Command::new(exe)
-        .env_clear() // no PATH
+    let mut command = Command::new(exe);
+    command.env_clear() // no PATH
         .arg(fd.to_string())
         .stdin(Stdio::null())
-        .stdout(Stdio::null())
-        .output().expect("run cloexec_tester program");
+        .stdout(Stdio::null());
+    configure(&mut command);
+    let output = command.output().expect("run cloexec_tester program");
     if !output.stderr.is_empty() {
         panic!("{}", String::from_utf8_lossy(&output.stderr));
     }
@@ -108,3 +114,38 @@ fn raw_not_cloexec() {
         let _ = libc::close(fds[1]);
     }
 }
+
+#[test]
+fn passed_to_child() {
+    let (a, _b) = UnixDatagram::pair().expect("create unix datagram pair");
+    let pass = |command: &mut Command| {
+        command.pass_sockets(&[ChildSocket::new(&a, 40)]).expect("pass socket");
+    };
+    assert!(!is_cloexec_in(40, pass));
+    assert!(is_cloexec_in(a.as_raw_fd(), pass), "original is still close-on-exec");
+}
+
+#[test]
+fn passed_with_swapped_numbers() {
+    let (stream, _) = UnixStream::pair().expect("create unix stream pair");
+    let (datagram, _) = UnixDatagram::pair().expect("create unix datagram pair");
+    let (stream_fd, datagram_fd) = (stream.as_raw_fd(), datagram.as_raw_fd());
+    let swap = |command: &mut Command| {
+        command.pass_sockets(&[
+            ChildSocket::new(&stream, datagram_fd),
+            ChildSocket::new(&datagram, stream_fd),
+        ]).expect("pass sockets");
+    };
+    assert!(!is_cloexec_in(stream_fd, swap));
+    assert!(!is_cloexec_in(datagram_fd, swap));
+}
+
+#[test]
+fn pass_invalid_numbers() {
+    let (a, b) = UnixStream::pair().expect("create unix stream pair");
+    let mut command = Command::new("true");
+    let twice = [ChildSocket::new(&a, 5), ChildSocket::new(&b, 5)];
+    assert_eq!(command.pass_sockets(&twice).unwrap_err().kind(), ErrorKind::InvalidInput);
+    let negative = [ChildSocket::new(&a, -1)];
+    assert_eq!(command.pass_sockets(&negative).unwrap_err().kind(), ErrorKind::InvalidInput);
+}
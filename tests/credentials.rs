@@ -244,3 +244,11 @@ fn peer_selinux_context() {
         }
     }
 }
+
+#[cfg(any(target_os="linux", target_os="android"))]
+#[test]
+fn scm_credentials_with_fds() {
+    // capabilities() sends credentials and a file descriptor in one message,
+    // which used to crash send_ancillary().
+    assert!(uds::capabilities().scm_credentials);
+}
@@ -0,0 +1,66 @@
+#![cfg(any(target_os="linux", target_os="android"))]
+
+use std::io::ErrorKind::*;
+use std::os::unix::net::UnixDatagram;
+
+use libc::{SOCK_DGRAM, SOCK_SEQPACKET};
+
+use uds::diag::{self, SocketState};
+use uds::{UnixSeqpacketConn, UnixSeqpacketListener, UnixSocketAddr};
+
+#[test]
+fn pair() {
+    let (a, b) = UnixSeqpacketConn::pair().expect("create seqpacket pair");
+    a.send(b"queued").unwrap();
+
+    let a_info = diag::socket_info(&a).expect("get info about own socket");
+    let b_info = diag::socket_info(&b).expect("get info about own socket");
+    assert_eq!(a_info.socket_type, SOCK_SEQPACKET);
+    assert_eq!(a_info.state, SocketState::Connected);
+    assert!(a_info.addr.is_unnamed());
+    assert_eq!(a_info.peer_inode, Some(b_info.inode));
+    assert_eq!(b_info.peer_inode, Some(a_info.inode));
+    assert_eq!(b_info.recv_queue_len, Some(6));
+    assert_eq!(a_info.recv_queue_len, Some(0));
+    if let Some(uid) = a_info.uid {
+        assert_eq!(uid, unsafe { libc::geteuid() });
+    }
+
+    let peer = diag::peer_info(&a).expect("get info about peer");
+    assert_eq!(peer.inode, b_info.inode);
+}
+
+#[test]
+fn listener_and_connections() {
+    let addr = UnixSocketAddr::from_abstract("uds diag listener").unwrap();
+    let listener = UnixSeqpacketListener::bind_unix_addr(&addr).unwrap();
+    let client = UnixSeqpacketConn::connect_unix_addr(&addr).unwrap();
+
+    let listener_info = diag::socket_info(&listener).unwrap();
+    assert_eq!(listener_info.state, SocketState::Listening);
+    assert_eq!(listener_info.addr, addr);
+    assert_eq!(listener_info.peer_inode, None);
+    assert_eq!(listener_info.recv_queue_len, Some(1), "one connection waiting to be accepted");
+
+    let (server, _) = listener.accept_unix_addr().unwrap();
+    assert_eq!(diag::peer_info(&client).unwrap().inode, diag::socket_info(&server).unwrap().inode);
+    assert_eq!(diag::peer_info(&server).unwrap().inode, diag::socket_info(&client).unwrap().inode);
+
+    let all = diag::all_sockets().expect("list all sockets");
+    assert!(all.iter().any(|socket| socket.inode == listener_info.inode ));
+}
+
+#[test]
+fn path_and_unconnected() {
+    let path = "diag.socket";
+    let _ = std::fs::remove_file(path);
+    let socket = UnixDatagram::bind(path).unwrap();
+    let info = diag::socket_info(&socket).unwrap();
+    let _ = std::fs::remove_file(path);
+
+    assert_eq!(info.socket_type, SOCK_DGRAM);
+    assert_eq!(info.state, SocketState::Unconnected);
+    assert_eq!(info.addr.as_pathname(), Some(std::path::Path::new(path)));
+    assert!(info.file_inode.is_some());
+    assert_eq!(diag::peer_info(&socket).unwrap_err().kind(), NotConnected);
+}
@@ -0,0 +1,88 @@
+use std::error::Error as _;
+use std::io::{self, ErrorKind};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::UnixStream;
+
+use uds::framed::{FramedStream, MAX_FDS};
+use uds::{Error, UnixSocketAddr};
+
+#[test]
+fn too_long_path() {
+    let path = "L".repeat(UnixSocketAddr::max_path_len()+1);
+    let error = UnixSocketAddr::from_path(&path).expect_err("create too long path address");
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    match Error::from_io(error) {
+        Error::AddressTooLong{ max } => assert_eq!(max, UnixSocketAddr::max_path_len()),
+        other => panic!("expected AddressTooLong, got {:?}", other),
+    }
+}
+
+#[test]
+fn too_long_raw_bytes() {
+    let bytes = vec![b'L'; UnixSocketAddr::max_path_len()+1];
+    let error = UnixSocketAddr::from_raw_bytes(&bytes).unwrap_err();
+    assert!(matches!(Error::from(error), Error::AddressTooLong{ .. }));
+}
+
+#[test]
+fn nul_in_path() {
+    let error = UnixSocketAddr::from_path("nul\0path").unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    assert!(matches!(Error::from_io(error), Error::NulInPath));
+}
+
+#[test]
+fn abstract_name() {
+    let too_long = vec![b'L'; UnixSocketAddr::max_abstract_len()+1];
+    let error = UnixSocketAddr::from_abstract(&too_long).unwrap_err();
+    if cfg!(any(target_os="linux", target_os="android")) {
+        assert!(matches!(Error::from_io(error), Error::AddressTooLong{ .. }));
+    } else {
+        assert_eq!(error.kind(), ErrorKind::AddrNotAvailable);
+        assert!(matches!(Error::from_io(error), Error::AbstractUnsupported));
+    }
+}
+
+#[test]
+fn too_many_fds() {
+    let (a, _b) = UnixStream::pair().unwrap();
+    let mut a = FramedStream::new(a);
+    let fds = vec![a.get_ref().as_raw_fd(); MAX_FDS+1];
+    let error = a.send_frame(b"fds", &fds).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    assert!(matches!(Error::from_io(error), Error::TooManyFds));
+}
+
+#[test]
+fn round_trip() {
+    let error = io::Error::from(Error::AncillaryTruncated);
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+    assert_eq!(error.to_string(), Error::AncillaryTruncated.to_string());
+    assert!(matches!(Error::from_io(error), Error::AncillaryTruncated));
+
+    let error = io::Error::from(Error::InvalidAncillaryBuffer);
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+    assert!(matches!(Error::from_io(error), Error::InvalidAncillaryBuffer));
+}
+
+#[test]
+fn os_errors_pass_through() {
+    let error = Error::from_io(io::Error::from_raw_os_error(libc::EBADF));
+    match error {
+        Error::Os(ref inner) => assert_eq!(inner.raw_os_error(), Some(libc::EBADF)),
+        ref other => panic!("expected Os, got {:?}", other),
+    }
+    let error = io::Error::from(error);
+    assert_eq!(error.raw_os_error(), Some(libc::EBADF));
+    assert!(error.get_ref().is_none());
+
+    let custom = io::Error::new(ErrorKind::InvalidData, "not from uds");
+    match Error::from_io(custom) {
+        Error::Os(inner) => {
+            assert_eq!(inner.to_string(), "not from uds");
+            assert!(inner.get_ref().is_some());
+        }
+        other => panic!("expected Os, got {:?}", other),
+    }
+    assert!(Error::TooManyFds.source().is_none());
+}
@@ -0,0 +1,118 @@
+use std::io::{ErrorKind, Read, Write};
+use std::os::unix::io::{AsRawFd, OwnedFd};
+use std::os::unix::net::UnixStream;
+use std::thread;
+
+#[cfg(feature="tokio")]
+use tokio_02 as tokio;
+
+use uds::framed::{FramedStream, MAX_FDS};
+use uds::UnixStreamExt;
+
+fn assert_connected(mut ours: UnixStream,  theirs: OwnedFd) {
+    let mut theirs = UnixStream::from(theirs);
+    theirs.write_all(b"ping").unwrap();
+    let mut buf = [0; 4];
+    ours.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"ping");
+}
+
+#[test]
+fn fds_stay_with_their_frame() {
+    let (a, b) = UnixStream::pair().unwrap();
+    let (mut a, mut b) = (FramedStream::new(a), FramedStream::new(b));
+    let (first, first_fd) = UnixStream::pair().unwrap();
+    let (second, second_fd) = UnixStream::pair().unwrap();
+    a.send_frame(b"one", &[first_fd.as_raw_fd()]).unwrap();
+    a.send_frame(b"", &[]).unwrap();
+    a.send_frame(b"three", &[second_fd.as_raw_fd()]).unwrap();
+
+    let (frame, mut fds) = b.recv_frame().unwrap();
+    assert_eq!((&frame[..], fds.len()), (&b"one"[..], 1));
+    assert_connected(first, fds.pop().unwrap());
+    let (frame, fds) = b.recv_frame().unwrap();
+    assert_eq!((frame.len(), fds.len()), (0, 0));
+    let (frame, mut fds) = b.recv_frame().unwrap();
+    assert_eq!((&frame[..], fds.len()), (&b"three"[..], 1));
+    assert_connected(second, fds.pop().unwrap());
+}
+
+#[test]
+fn large_frames() {
+    let (a, b) = UnixStream::pair().unwrap();
+    let (mut a, mut b) = (FramedStream::new(a), FramedStream::new(b));
+    let data = (0..300_000).map(|i| i as u8 ).collect::<Vec<u8>>();
+    let sending = {
+        let data = data.clone();
+        thread::spawn(move || {
+            let fd = a.get_ref().as_raw_fd();
+            a.send_frame(&data, &[fd]).unwrap();
+            a.send_frame(&data, &[fd, fd]).unwrap();
+        })
+    };
+    let (frame, fds) = b.recv_frame().unwrap();
+    assert_eq!((frame == data, fds.len()), (true, 1));
+    let (frame, fds) = b.recv_frame().unwrap();
+    assert_eq!((frame == data, fds.len()), (true, 2));
+    sending.join().unwrap();
+}
+
+#[test]
+fn closed() {
+    let (a, b) = UnixStream::pair().unwrap();
+    let mut b = FramedStream::new(b);
+    drop(a);
+    assert_eq!(b.recv_frame().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+
+    let (mut a, b) = UnixStream::pair().unwrap();
+    let mut b = FramedStream::new(b);
+    a.write_all(&10u32.to_le_bytes()).unwrap();
+    a.write_all(&[0, 0, b'a']).unwrap();
+    drop(a);
+    assert_eq!(b.recv_frame().unwrap_err().kind(), ErrorKind::UnexpectedEof);
+}
+
+#[test]
+fn invalid_fds() {
+    let (a, b) = UnixStream::pair().unwrap();
+    let mut b = FramedStream::new(b);
+    let mut header = 1u32.to_le_bytes().to_vec();
+    header.extend_from_slice(&2u16.to_le_bytes());
+    a.send_fds(&header, &[a.as_raw_fd()]).unwrap();
+    (&a).write_all(b"x").unwrap();
+    assert_eq!(b.recv_frame().unwrap_err().kind(), ErrorKind::InvalidData);
+
+    let (a, b) = UnixStream::pair().unwrap();
+    let mut b = FramedStream::new(b);
+    header[4..].copy_from_slice(&0u16.to_le_bytes());
+    (&a).write_all(&header).unwrap();
+    a.send_fds(b"x", &[a.as_raw_fd()]).unwrap();
+    assert_eq!(b.recv_frame().unwrap_err().kind(), ErrorKind::InvalidData);
+
+    let mut a = FramedStream::new(a);
+    let too_many = vec![a.get_ref().as_raw_fd(); MAX_FDS+1];
+    assert_eq!(a.send_frame(b"", &too_many).unwrap_err().kind(), ErrorKind::InvalidInput);
+}
+
+#[cfg(feature="tokio")]
+#[tokio::test]
+async fn tokio_framed() {
+    use futures::StreamExt;
+    use uds::tokio::FramedStream;
+
+    let (mut a, mut b) = FramedStream::pair().unwrap();
+    let (stream, fd) = UnixStream::pair().unwrap();
+    let data = vec![3; 200_000];
+    let sending = tokio::spawn(async move {
+        a.send_frame(&data, &[fd.as_raw_fd()]).await.unwrap();
+        a.send_frame(b"last", &[]).await.unwrap();
+    });
+
+    let (frame, mut fds) = b.recv_frame().await.unwrap();
+    assert_eq!(frame, vec![3; 200_000]);
+    assert_connected(stream, fds.pop().unwrap());
+    let (frame, fds) = b.next().await.unwrap().unwrap();
+    assert_eq!((&frame[..], fds.len()), (&b"last"[..], 0));
+    sending.await.unwrap();
+    assert!(b.next().await.is_none());
+}
@@ -0,0 +1,96 @@
+use std::io::{ErrorKind, Write};
+use std::os::unix::io::AsRawFd;
+use std::os::unix::net::{UnixDatagram, UnixListener, UnixStream};
+use std::thread;
+
+use uds::handoff::{self, Listener};
+use uds::{ShutdownHandle, UnixListenerExt, UnixStreamExt};
+
+#[test]
+fn stream_listener_keeps_nonblocking_state() {
+    let (to_new, to_old) = UnixStream::pair().unwrap();
+    let (listener, temp) = UnixListener::bind_temp().unwrap().into_parts();
+    listener.set_nonblocking(true).unwrap();
+    let _queued = UnixStream::connect_to_unix_addr(temp.addr()).unwrap();
+
+    let old = thread::spawn(move || handoff::send_listener(&to_new, &listener, None) );
+    let (listener, addr) = handoff::receive_listener(&to_old).unwrap();
+    old.join().unwrap().unwrap();
+
+    assert_eq!(&addr, temp.addr());
+    assert_eq!(listener.local_addr().unwrap(), addr);
+    let listener = match listener {
+        Listener::Stream(listener) => listener,
+        other => panic!("expected a stream listener, got {:?}", other),
+    };
+    listener.accept().expect("accept connection queued before the handoff");
+    assert_eq!(listener.accept().unwrap_err().kind(), ErrorKind::WouldBlock);
+}
+
+#[cfg(not(target_vendor="apple"))]
+#[test]
+fn seqpacket_listener_stops_old_accept_loop() {
+    use uds::{nonblocking, UnixSeqpacketConn, UnixSeqpacketListener};
+
+    let (to_new, to_old) = UnixStream::pair().unwrap();
+    let (listener, temp) = UnixSeqpacketListener::bind_temp().unwrap().into_parts();
+    let shutdown = ShutdownHandle::new().unwrap();
+    let old_loop = {
+        let listener = listener.try_clone().unwrap();
+        let shutdown = shutdown.clone();
+        thread::spawn(move || {
+            let mut accepted = 0;
+            while listener.accept_unless_shutdown(&shutdown).unwrap().is_some() {
+                accepted += 1;
+            }
+            accepted
+        })
+    };
+
+    let listener = listener.into_nonblocking().unwrap();
+    let old = thread::spawn(move || handoff::send_listener(&to_new, &listener, Some(&shutdown)) );
+    let (listener, _) = handoff::receive_listener(&to_old).unwrap();
+    old.join().unwrap().unwrap();
+    assert_eq!(old_loop.join().unwrap(), 0);
+
+    let listener = match listener {
+        Listener::NonblockingSeqpacket(listener) => listener,
+        other => panic!("expected a nonblocking seqpacket listener, got {:?}", other),
+    };
+    let _client = UnixSeqpacketConn::connect_unix_addr(temp.addr()).unwrap();
+    let (conn, _): (nonblocking::UnixSeqpacketConn, _) = listener.accept_unix_addr().unwrap();
+    assert_eq!(conn.recv(&mut[0; 10]).unwrap_err().kind(), ErrorKind::WouldBlock);
+}
+
+#[test]
+fn not_taken_over() {
+    let (to_new, mut to_old) = UnixStream::pair().unwrap();
+    let listener = UnixListener::bind_temp().unwrap();
+    let shutdown = ShutdownHandle::new().unwrap();
+    to_old.write_all(&[0]).unwrap();
+    let result = handoff::send_listener(&to_new, &listener, Some(&shutdown));
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidData);
+    assert!(!shutdown.is_shutdown());
+
+    drop(to_old);
+    let result = handoff::send_listener(&to_new, &listener, Some(&shutdown));
+    assert!(result.is_err());
+    assert!(!shutdown.is_shutdown());
+}
+
+#[test]
+fn rejects_unsupported_sockets() {
+    let (to_new, to_old) = UnixStream::pair().unwrap();
+    let (datagram, _) = UnixDatagram::pair().unwrap();
+    let result = handoff::send_listener(&to_new, &datagram, None);
+    assert_eq!(result.unwrap_err().kind(), ErrorKind::InvalidInput);
+
+    to_new.send_fds(b"not a handoff message", &[datagram.as_raw_fd()]).unwrap();
+    let error = handoff::receive_listener(&to_old).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidData);
+
+    let (to_new, to_old) = UnixStream::pair().unwrap();
+    drop(to_new);
+    let error = handoff::receive_listener(&to_old).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::UnexpectedEof);
+}
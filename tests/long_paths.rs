@@ -0,0 +1,164 @@
+use std::fs;
+use std::io::{self, Read, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::os::unix::net::{UnixDatagram, UnixListener, UnixStream};
+use std::path::{Path, PathBuf};
+
+use uds::{UnixDatagramExt, UnixListenerExt, UnixSocketAddr, UnixStreamExt};
+
+/// Creates a directory whose path alone is longer than `sun_path`.
+fn long_dir(test: &str) -> PathBuf {
+    let mut dir = std::env::temp_dir();
+    dir.push(format!("uds long paths {} {}", test, std::process::id()));
+    let _ = fs::remove_dir_all(&dir);
+    while dir.as_os_str().len() <= UnixSocketAddr::max_path_len() {
+        dir.push("a directory with a long name");
+    }
+    fs::create_dir_all(&dir).expect("create deep directory");
+    dir
+}
+
+fn remove_long_dir(test: &str) {
+    let mut dir = std::env::temp_dir();
+    dir.push(format!("uds long paths {} {}", test, std::process::id()));
+    let _ = fs::remove_dir_all(dir);
+}
+
+#[test]
+fn stream() {
+    let path = long_dir("stream").join("stream.socket");
+    assert!(UnixSocketAddr::from_path(&path).is_err());
+
+    let (listener, listener_path) = UnixListener::bind_path(&path).expect("bind to long path");
+    assert_eq!(listener_path, path);
+    assert!(path.exists(), "socket file was created in the right directory");
+
+    // after the call the returned path must still work
+    let mut client = UnixStream::connect_to_path(&listener_path).expect("connect to long path");
+    let (mut server, _) = listener.accept_unix_addr().unwrap();
+    client.write_all(b"deep").unwrap();
+    let mut buf = [0; 4];
+    server.read_exact(&mut buf).unwrap();
+    assert_eq!(&buf, b"deep");
+    remove_long_dir("stream");
+}
+
+#[test]
+fn datagram() {
+    let dir = long_dir("datagram");
+    let a_path = dir.join("a.socket");
+    let b_path = dir.join("b.socket");
+
+    let a = UnixDatagram::unbound().unwrap();
+    assert_eq!(a.bind_to_path(&a_path).expect("bind to long path"), a_path);
+    let b = UnixDatagram::unbound().unwrap();
+    let b_reachable = b.bind_to_path(&b_path).expect("bind to long path");
+    a.connect_to_path(&b_reachable).expect("connect to long path");
+    a.send(b"deep").unwrap();
+    assert_eq!(b.recv(&mut[0; 10]).unwrap(), 4);
+    assert!(a_path.exists() && b_path.exists());
+    remove_long_dir("datagram");
+}
+
+#[cfg(not(target_vendor="apple"))]
+#[test]
+fn seqpacket() {
+    use uds::{UnixSeqpacketConn, UnixSeqpacketListener};
+
+    let path = long_dir("seqpacket").join("seqpacket.socket");
+    let listener = UnixSeqpacketListener::bind(&path).expect("bind to long path");
+    let client = UnixSeqpacketConn::connect(&path).expect("connect to long path");
+    let (server, _) = listener.accept_unix_addr().unwrap();
+    client.send(b"deep").unwrap();
+    assert_eq!(server.recv(&mut[0; 10]).unwrap(), 4);
+    remove_long_dir("seqpacket");
+}
+
+#[test]
+fn short_paths_are_unaffected() {
+    let path = "short long_paths.socket";
+    let _ = fs::remove_file(path);
+    let (listener, listener_path) = UnixListener::bind_path(path).unwrap();
+    assert_eq!(listener_path, Path::new(path));
+    assert_eq!(listener.local_unix_addr().unwrap(), UnixSocketAddr::from_path(path).unwrap());
+    let _client = UnixStream::connect_to_path(path).unwrap();
+    let _ = fs::remove_file(path);
+}
+
+#[test]
+fn missing_directory() {
+    let mut path = long_dir("missing");
+    path.push("not created");
+    path.push("x.socket");
+    assert!(UnixStream::connect_to_path(&path).is_err());
+    remove_long_dir("missing");
+}
+
+/// Implements only the required methods, to test the provided ones.
+struct MinimalListener(UnixListener);
+
+impl AsRawFd for MinimalListener {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+
+impl FromRawFd for MinimalListener {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        MinimalListener(UnixListener::from_raw_fd(fd))
+    }
+}
+
+impl UnixListenerExt for MinimalListener {
+    type Conn = UnixStream;
+    fn bind_unix_addr(on: &UnixSocketAddr) -> Result<Self, io::Error> {
+        UnixListener::bind_unix_addr(on).map(MinimalListener)
+    }
+    fn accept_unix_addr(&self) -> Result<(UnixStream, UnixSocketAddr), io::Error> {
+        self.0.accept_unix_addr()
+    }
+}
+
+/// Implements only the required methods, to test the provided ones.
+struct MinimalStream(UnixStream);
+
+impl AsRawFd for MinimalStream {
+    fn as_raw_fd(&self) -> RawFd {
+        self.0.as_raw_fd()
+    }
+}
+
+impl FromRawFd for MinimalStream {
+    unsafe fn from_raw_fd(fd: RawFd) -> Self {
+        MinimalStream(UnixStream::from_raw_fd(fd))
+    }
+}
+
+impl UnixStreamExt for MinimalStream {
+    fn connect_to_unix_addr(addr: &UnixSocketAddr) -> Result<Self, io::Error> {
+        UnixStream::connect_to_unix_addr(addr).map(MinimalStream)
+    }
+    fn connect_from_to_unix_addr(from: &UnixSocketAddr,  to: &UnixSocketAddr)
+    -> Result<Self, io::Error> {
+        UnixStream::connect_from_to_unix_addr(from, to).map(MinimalStream)
+    }
+}
+
+#[test]
+fn provided_methods() {
+    let path = long_dir("provided").join("provided.socket");
+    let (listener, listener_path) = MinimalListener::bind_path(&path).expect("bind to long path");
+    assert_eq!(listener_path, path);
+    let _client = MinimalStream::connect_to_path(&listener_path).expect("connect to long path");
+    listener.accept_unix_addr().unwrap();
+
+    let dir_path = listener_path.parent().unwrap();
+    let dir = fs::File::open(dir_path).unwrap();
+    let (listener, at_path) = MinimalListener::bind_at(dir.as_raw_fd(), "at.socket")
+        .expect("bind relative to directory");
+    assert_eq!(at_path, fs::canonicalize(dir_path).unwrap().join("at.socket"));
+    let _client = MinimalStream::connect_at(dir.as_raw_fd(), "at.socket")
+        .expect("connect relative to directory");
+    listener.accept_unix_addr().unwrap();
+    remove_long_dir("provided");
+}
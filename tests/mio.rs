@@ -1,4 +1,4 @@
-#![cfg(any(all(feature="mio-uds", feature="mio"), feature="mio_07"))]
+#![cfg(any(all(feature="mio-uds", feature="mio"), feature="mio_07", feature="mio_08", feature="mio_1"))]
 
 extern crate uds;
 #[cfg(all(feature="mio-uds", feature="mio"))]
@@ -9,6 +9,8 @@ extern crate mio;
 extern crate mio_07;
 #[cfg(feature = "mio_08")]
 extern crate mio_08;
+#[cfg(feature = "mio_1")]
+extern crate mio_1;
 
 use std::fs::remove_file;
 use std::{io::{Read, Write}, path::Path, time::Duration};
@@ -136,3 +138,9 @@ fn mio_07_stream() {
 fn mio_08_stream() {
     mio_streams!(mio_08);
 }
+
+#[cfg(feature = "mio_1")]
+#[test]
+fn mio_1_stream() {
+    mio_streams!(mio_1);
+}
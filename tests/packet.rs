@@ -0,0 +1,210 @@
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::io::{self, Read, Write};
+use std::os::unix::io::{AsRawFd, FromRawFd, RawFd};
+use std::os::unix::net::UnixStream;
+use std::thread;
+
+#[cfg(feature="tokio")]
+use tokio_02 as tokio;
+
+use uds::packet::{FdPassing, PacketConn, PacketListener, PacketRecv, PacketSend, PeerCredentials, Transport};
+
+#[cfg(not(target_vendor="apple"))]
+const TRANSPORTS: &[Transport] = &[Transport::Seqpacket, Transport::Stream];
+#[cfg(target_vendor="apple")]
+const TRANSPORTS: &[Transport] = &[Transport::Stream];
+
+#[test]
+fn boundaries_are_kept() {
+    for &transport in TRANSPORTS {
+        let (a, b) = PacketConn::pair_with(transport).unwrap();
+        assert_eq!((a.transport(), b.transport()), (transport, transport));
+        assert_eq!(a.send(b"abc").unwrap(), 3);
+        assert_eq!(a.send(b"").unwrap(), 0);
+        assert_eq!(a.send(b"defgh").unwrap(), 5);
+
+        let mut buf = [0; 10];
+        assert_eq!(b.recv(&mut buf).unwrap(), 3);
+        assert_eq!(&buf[..3], b"abc");
+        assert_eq!(b.recv(&mut buf).unwrap(), 0);
+        assert_eq!(b.recv_fds(&mut buf[..2], &mut[]).unwrap(), (2, true, 0));
+        assert_eq!(&buf[..2], b"de");
+
+        drop(a);
+        assert_eq!(b.recv(&mut buf).unwrap(), 0);
+    }
+}
+
+#[test]
+fn fds_stay_with_their_packet() {
+    for &transport in TRANSPORTS {
+        let (a, b) = PacketConn::pair_with(transport).unwrap();
+        let (mut ours, theirs) = UnixStream::pair().unwrap();
+        a.send(b"before").unwrap();
+        a.send_fds(b"with", &[theirs.as_raw_fd()]).unwrap();
+        drop(theirs);
+
+        let mut buf = [0; 10];
+        let mut fd_buf = [-1 as RawFd; 2];
+        assert_eq!(b.recv_fds(&mut buf, &mut fd_buf).unwrap(), (6, false, 0));
+        assert_eq!(b.recv_fds(&mut buf, &mut fd_buf).unwrap(), (4, false, 1));
+        let mut theirs = unsafe { UnixStream::from_raw_fd(fd_buf[0]) };
+        theirs.write_all(b"x").unwrap();
+        ours.read_exact(&mut buf[..1]).unwrap();
+        assert_eq!(buf[0], b'x');
+    }
+}
+
+#[test]
+fn listener_and_credentials() {
+    for &transport in TRANSPORTS {
+        let listener = PacketListener::bind_temp_with(transport).unwrap();
+        assert_eq!(listener.transport(), transport);
+        // falls back to emulation when the listener is a stream socket
+        let client = PacketConn::connect_unix_addr(listener.addr()).unwrap();
+        assert_eq!(client.transport(), transport);
+        let (server, _) = listener.accept_unix_addr().unwrap();
+        assert_eq!(server.transport(), transport);
+        assert_eq!(server.local_unix_addr().unwrap(), *listener.addr());
+
+        let credentials = server.initial_peer_credentials().unwrap();
+        assert_eq!(credentials.euid(), unsafe { libc::geteuid() });
+        client.send(b"hello").unwrap();
+        assert_eq!(server.recv(&mut[0; 10]).unwrap(), 5);
+    }
+}
+
+#[test]
+fn connect_to_path() {
+    for &transport in TRANSPORTS {
+        let temp = uds::TempSocketPath::new_path().unwrap();
+        let path = temp.addr().as_pathname().unwrap();
+        let listener = PacketListener::bind_unix_addr_with(temp.addr(), transport).unwrap();
+        let client = PacketConn::connect(path).unwrap();
+        assert_eq!(client.transport(), transport);
+        listener.accept_unix_addr().unwrap();
+        drop(listener);
+        let error = PacketConn::connect(path).map(|_| () ).unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::ConnectionRefused);
+    }
+}
+
+#[test]
+fn large_packets_from_clones() {
+    let (a, b) = PacketConn::pair_with(Transport::Stream).unwrap();
+    let packet = vec![9u8; 500_000];
+    let clone = a.try_clone().unwrap();
+    let sender = thread::spawn(move || {
+        clone.send(&vec![9u8; 500_000]).unwrap();
+        a.send(b"after").unwrap();
+    });
+    let mut buf = vec![0; 600_000];
+    assert_eq!(b.recv(&mut buf).unwrap(), packet.len());
+    assert!(buf[..packet.len()] == packet[..]);
+    assert_eq!(b.try_clone().unwrap().recv(&mut buf).unwrap(), 5);
+    sender.join().unwrap();
+}
+
+/// Answers every request with its length.
+fn serve_one<C: PacketSend+PacketRecv>(conn: &C) -> io::Result<()> {
+    let mut buf = [0; 100];
+    let received = conn.recv_packet(&mut buf)?;
+    conn.send_packet(&[received as u8]).map(|_| () )
+}
+
+fn request<C: FdPassing+AsRawFd>(client: &C,  server: &C) {
+    client.send_packet_fds(b"four", &[client.as_raw_fd()]).unwrap();
+    let mut fd_buf = [-1; 1];
+    assert_eq!(server.recv_packet_fds(&mut[0; 10], &mut fd_buf).unwrap(), (4, false, 1));
+    unsafe { libc::close(fd_buf[0]) };
+    client.send_packet(b"abc").unwrap();
+    serve_one(server).unwrap();
+    let mut response = [0; 1];
+    assert_eq!(client.recv_packet(&mut response).unwrap(), 1);
+    assert_eq!(response[0], 3);
+}
+
+fn request_with_credentials<C: FdPassing+PeerCredentials+AsRawFd>(client: &C,  server: &C) {
+    request(client, server);
+    assert_eq!(server.peer_credentials().unwrap().euid(), unsafe { libc::geteuid() });
+}
+
+#[test]
+fn generic_over_packet_sockets() {
+    #[cfg(not(target_vendor="apple"))] {
+        let (a, b) = uds::UnixSeqpacketConn::pair().unwrap();
+        request_with_credentials(&a, &b);
+        let (a, b) = uds::nonblocking::UnixSeqpacketConn::pair().unwrap();
+        request_with_credentials(&a, &b);
+    }
+    for &transport in TRANSPORTS {
+        let (a, b) = PacketConn::pair_with(transport).unwrap();
+        request_with_credentials(&a, &b);
+    }
+    let (a, b) = std::os::unix::net::UnixDatagram::pair().unwrap();
+    request(&a, &b);
+}
+
+/// Replays canned requests and records responses.
+#[derive(Default)]
+struct Double {
+    requests: RefCell<VecDeque<Vec<u8>>>,
+    responses: RefCell<Vec<Vec<u8>>>,
+}
+impl PacketSend for Double {
+    fn send_packet(&self,  packet: &[u8]) -> io::Result<usize> {
+        self.responses.borrow_mut().push(packet.to_vec());
+        Ok(packet.len())
+    }
+}
+impl PacketRecv for Double {
+    fn recv_packet(&self,  buffer: &mut[u8]) -> io::Result<usize> {
+        let request = self.requests.borrow_mut().pop_front().unwrap_or_default();
+        let len = request.len().min(buffer.len());
+        buffer[..len].copy_from_slice(&request[..len]);
+        Ok(len)
+    }
+}
+
+#[test]
+fn test_double() {
+    let double = Double::default();
+    double.requests.borrow_mut().push_back(b"hello".to_vec());
+    serve_one(&double).unwrap();
+    assert_eq!(double.responses.borrow()[..], [vec![5]]);
+}
+
+#[cfg(all(feature="tokio", not(target_vendor="apple")))]
+#[tokio::test]
+async fn async_traits() {
+    use futures::future::poll_fn;
+    use std::pin::Pin;
+    use uds::packet::{AsyncFdPassing, AsyncPacketRecv};
+
+    async fn echo<C: AsyncFdPassing+Unpin>(conn: &mut C) -> io::Result<(usize, usize)> {
+        let mut buf = [0; 20];
+        let mut fd_buf = [-1; 2];
+        let (len, _, fds) = poll_fn(|cx| {
+            Pin::new(&mut *conn).poll_recv_packet_fds(cx, &mut buf, &mut fd_buf)
+        }).await?;
+        let sent = poll_fn(|cx| {
+            Pin::new(&mut *conn).poll_send_packet_fds(cx, &buf[..len], &fd_buf[..fds])
+        }).await;
+        for &fd in &fd_buf[..fds] {
+            unsafe { libc::close(fd) };
+        }
+        sent?;
+        Ok((len, fds))
+    }
+
+    let (mut a, mut b) = uds::tokio::UnixSeqpacketConn::pair().unwrap();
+    let server = tokio::spawn(async move { echo(&mut b).await.unwrap() });
+    let fd = a.as_raw_fd();
+    poll_fn(|cx| Pin::new(&mut a).poll_send_packet_fds(cx, b"ping", &[fd]) ).await.unwrap();
+    assert_eq!(server.await.unwrap(), (4, 1));
+    let mut buf = [0; 10];
+    let len = poll_fn(|cx| Pin::new(&mut a).poll_recv_packet(cx, &mut buf) ).await.unwrap();
+    assert_eq!(&buf[..len], b"ping");
+    assert!(a.peer_credentials().is_ok());
+}
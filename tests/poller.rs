@@ -0,0 +1,90 @@
+use std::io::Write;
+use std::os::unix::net::{UnixDatagram, UnixListener, UnixStream};
+use std::time::{Duration, Instant};
+
+use uds::{Interest, Poller, UnixDatagramExt, UnixListenerExt, UnixStreamExt};
+
+#[test]
+fn stream_wait() {
+    let (mut a, b) = UnixStream::pair().unwrap();
+    let timeout = Duration::from_millis(50);
+    let before = Instant::now();
+    assert!(!b.wait_readable(Some(timeout)).unwrap());
+    assert!(before.elapsed() >= timeout);
+    assert!(b.wait_writable(Some(timeout)).unwrap());
+
+    a.write_all(b"ready").unwrap();
+    assert!(b.wait_readable(None).unwrap());
+    drop(a);
+    assert!(b.wait_readable(Some(Duration::ZERO)).unwrap(), "buffered data");
+}
+
+#[test]
+fn datagram_and_listener_wait() {
+    let (a, b) = UnixDatagram::pair().unwrap();
+    assert!(!a.wait_readable(Some(Duration::ZERO)).unwrap());
+    b.send(b"datagram").unwrap();
+    assert!(a.wait_readable(Some(Duration::from_secs(1))).unwrap());
+    assert!(a.wait_writable(Some(Duration::ZERO)).unwrap());
+
+    let listener = UnixListener::bind_temp().unwrap();
+    assert!(!listener.wait_readable(Some(Duration::from_millis(10))).unwrap());
+    let _client = UnixStream::connect_to_unix_addr(listener.addr()).unwrap();
+    assert!(listener.wait_readable(Some(Duration::from_secs(1))).unwrap());
+}
+
+#[cfg(not(target_vendor="apple"))]
+#[test]
+fn seqpacket_wait() {
+    use uds::{UnixSeqpacketConn, UnixSeqpacketListener};
+
+    let listener = UnixSeqpacketListener::bind_temp().unwrap();
+    assert!(!listener.wait_readable(Some(Duration::ZERO)).unwrap());
+    let client = UnixSeqpacketConn::connect_unix_addr(listener.addr()).unwrap();
+    assert!(listener.wait_readable(Some(Duration::from_secs(1))).unwrap());
+    let (server, _) = listener.accept_unix_addr().unwrap();
+
+    assert!(!server.wait_readable(Some(Duration::from_millis(10))).unwrap());
+    assert!(client.wait_writable(Some(Duration::ZERO)).unwrap());
+    client.send(b"packet").unwrap();
+    assert!(server.wait_readable(Some(Duration::from_secs(1))).unwrap());
+}
+
+#[test]
+fn poller() {
+    let (mut a1, b1) = UnixStream::pair().unwrap();
+    let (a2, b2) = UnixDatagram::pair().unwrap();
+    let mut poller = Poller::new();
+    assert!(poller.is_empty());
+    poller.add(&b1, 1, Interest::Readable);
+    poller.add(&b2, 2, Interest::Readable);
+    assert_eq!(poller.len(), 2);
+
+    assert_eq!(poller.poll(Some(Duration::from_millis(10))).unwrap(), 0);
+    assert_eq!(poller.events().count(), 0);
+
+    a2.send(b"two").unwrap();
+    assert_eq!(poller.poll(None).unwrap(), 1);
+    let events = poller.events().collect::<Vec<_>>();
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].token(), 2);
+    assert!(events[0].is_readable());
+    assert!(!events[0].is_writable());
+
+    a1.write_all(b"one").unwrap();
+    assert_eq!(poller.poll(Some(Duration::from_secs(1))).unwrap(), 2, "level-triggered");
+
+    assert!(poller.modify(1, Interest::Both));
+    assert!(poller.remove(2));
+    assert!(!poller.remove(2));
+    assert!(!poller.modify(2, Interest::Writable));
+    assert_eq!(poller.poll(Some(Duration::ZERO)).unwrap(), 1);
+    let event = poller.events().next().unwrap();
+    assert_eq!(event.token(), 1);
+    assert!(event.is_readable() && event.is_writable());
+    assert!(!event.is_hangup() && !event.is_error());
+
+    drop(a1);
+    assert_eq!(poller.poll(Some(Duration::ZERO)).unwrap(), 1);
+    assert!(poller.events().next().unwrap().is_readable());
+}
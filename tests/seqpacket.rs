@@ -7,7 +7,7 @@ use std::os::unix::io::AsRawFd;
 use std::time::{Duration, Instant};
 
 use uds::nonblocking::UnixSeqpacketConn as NonblockingUnixSeqpacketConn;
-use uds::{UnixSeqpacketConn, UnixSeqpacketListener};
+use uds::{UnixSeqpacketConn, UnixSeqpacketListener, UnixSocketAddr};
 
 #[test]
 fn seqpacket_is_supported() {
@@ -203,6 +203,67 @@ fn shutdown() {
     }
 }
 
+#[test]
+fn queue_lengths() {
+    let (a, b) = NonblockingUnixSeqpacketConn::pair().unwrap();
+    assert_eq!(a.recv_queue_len().unwrap(), 0);
+    assert_eq!(b.recv_queue_len().unwrap(), 0);
+
+    a.send(b"slow").unwrap();
+    a.send(b"consumer").unwrap();
+    if cfg!(any(target_os="linux", target_os="android")) {
+        assert_eq!(b.recv_queue_len().unwrap(), 12);
+        let queued = a.send_queue_len().unwrap();
+        assert!(queued >= 12, "send queue length {} is less than what was sent", queued);
+        assert_eq!(b.send_queue_len().unwrap(), 0);
+    } else {
+        assert!(b.recv_queue_len().unwrap() >= 4);
+    }
+    assert_eq!(a.recv_queue_len().unwrap(), 0);
+
+    b.recv(&mut[0; 10]).unwrap();
+    b.recv(&mut[0; 10]).unwrap();
+    assert_eq!(b.recv_queue_len().unwrap(), 0);
+    if cfg!(any(target_os="linux", target_os="android")) {
+        assert_eq!(a.send_queue_len().unwrap(), 0);
+    }
+}
+
+#[test]
+fn peek_cursor() {
+    let (a, b) = NonblockingUnixSeqpacketConn::pair().unwrap();
+    a.send(b"first").unwrap();
+    a.send(b"second").unwrap();
+    if cfg!(not(any(target_os="linux", target_os="android"))) {
+        assert!(b.peek_cursor().is_err());
+        return;
+    }
+
+    let mut buf = [0; 10];
+    let mut cursor = b.peek_cursor().unwrap();
+    assert_eq!(cursor.offset().unwrap(), 0);
+    assert_eq!(cursor.peek_next(&mut buf[..3]).unwrap(), 3);
+    assert_eq!(&buf[..3], b"fir");
+    assert_eq!(cursor.offset().unwrap(), 3);
+    assert_eq!(cursor.peek_next(&mut buf).unwrap(), 2);
+    assert_eq!(&buf[..2], b"st");
+    assert_eq!(cursor.peek_next(&mut buf).unwrap(), 6);
+    assert_eq!(&buf[..6], b"second");
+    assert_eq!(cursor.peek_next(&mut buf).unwrap_err().kind(), WouldBlock);
+
+    // receiving moves the offset back
+    assert_eq!(b.recv(&mut buf).unwrap(), 5);
+    assert_eq!(cursor.offset().unwrap(), 6);
+    cursor.rewind().unwrap();
+    assert_eq!(cursor.peek_next(&mut buf[..3]).unwrap(), 3);
+    assert_eq!(&buf[..3], b"sec");
+
+    drop(cursor);
+    assert_eq!(b.peek(&mut buf).unwrap(), 6);
+    assert_eq!(b.peek(&mut buf).unwrap(), 6);
+    assert_eq!(&buf[..6], b"second");
+}
+
 #[cfg_attr(not(any(target_os="illumos", target_os="solaris")), test)]
 #[cfg_attr(any(target_os="illumos", target_os="solaris"), allow(unused))]
 fn read_timeout() {
@@ -276,12 +337,17 @@ fn write_timeout() {
 
 #[test]
 fn accept_timeout() {
-    let addr = "accept_timeout.sock";
     let timeout = Duration::new(0, 250_000_000);
-    let _ = std::fs::remove_file(addr);
-    let listener = UnixSeqpacketListener::bind(addr)
-        .expect("create seqpacket listener");
-    std::fs::remove_file(addr).expect("delete created socket file");
+    let listener = if UnixSocketAddr::has_abstract_addresses() {
+        UnixSeqpacketListener::autobind().expect("create seqpacket listener").0
+    } else {
+        let addr = "accept_timeout.sock";
+        let _ = std::fs::remove_file(addr);
+        let listener = UnixSeqpacketListener::bind(addr)
+            .expect("create seqpacket listener");
+        std::fs::remove_file(addr).expect("delete created socket file");
+        listener
+    };
 
     assert_eq!(listener.timeout().expect("get default timeout"), None);
     listener.set_timeout(None).expect("disable timeout");
@@ -301,3 +367,121 @@ fn accept_timeout() {
         assert!(elapsed < 2*timeout, "elapsed: {:?}, timeout: {:?}", elapsed, timeout);
     }
 }
+
+#[cfg(any(target_os="linux", target_os="android"))]
+#[test]
+fn autobind() {
+    let (listener, listener_addr) = UnixSeqpacketListener::autobind().unwrap();
+    assert_eq!(listener.local_unix_addr().unwrap(), listener_addr);
+    let (other_listener, other_addr) = UnixSeqpacketListener::autobind().unwrap();
+    assert_ne!(listener_addr, other_addr);
+    drop(other_listener);
+
+    let (conn, conn_addr) = UnixSeqpacketConn::connect_autobound(&listener_addr).unwrap();
+    assert_eq!(conn.local_unix_addr().unwrap(), conn_addr);
+    let (_, accepted_from) = listener.accept_unix_addr().unwrap();
+    assert_eq!(accepted_from, conn_addr);
+}
+
+#[test]
+fn accept_with_deadline() {
+    let listener = UnixSeqpacketListener::bind_temp().unwrap();
+    let timeout = Duration::from_millis(100);
+    let before = Instant::now();
+    let err = listener.accept_timeout(timeout).expect_err("nobody connected");
+    let elapsed = before.elapsed();
+    assert_eq!(err.kind(), TimedOut);
+    assert!(elapsed >= timeout, "elapsed: {:?}, timeout: {:?}", elapsed, timeout);
+    assert!(elapsed < 5*timeout, "elapsed: {:?}, timeout: {:?}", elapsed, timeout);
+    assert_eq!(listener.accept_until(Instant::now()).unwrap_err().kind(), TimedOut);
+
+    let _client = UnixSeqpacketConn::connect_unix_addr(listener.addr()).unwrap();
+    listener.accept_timeout(timeout).expect("accept pending connection");
+    let _client = UnixSeqpacketConn::connect_unix_addr(listener.addr()).unwrap();
+    listener.accept_timeout(Duration::MAX).expect("accept without overflowing");
+}
+
+#[test]
+fn accept_timeout_leaves_listener_blocking() {
+    let listener = UnixSeqpacketListener::bind_temp().unwrap();
+    let is_nonblocking = |fd| unsafe { libc::fcntl(fd, libc::F_GETFL) } & libc::O_NONBLOCK != 0;
+    std::thread::scope(|scope| {
+        let waiting = scope.spawn(|| listener.accept_timeout(Duration::from_millis(100)) );
+        std::thread::sleep(Duration::from_millis(20));
+        // other threads and clones share the flag
+        assert!(!is_nonblocking(listener.as_raw_fd()));
+        assert_eq!(waiting.join().unwrap().unwrap_err().kind(), TimedOut);
+    });
+
+    let client = UnixSeqpacketConn::connect_unix_addr(listener.addr()).unwrap();
+    let (accepted, _) = listener.accept_timeout(Duration::from_millis(100)).unwrap();
+    assert!(!is_nonblocking(accepted.as_raw_fd()));
+    client.send(b"blocking").unwrap();
+    assert_eq!(accepted.recv(&mut[0; 10]).unwrap(), 8);
+}
+
+#[test]
+fn accept_timeout_retries_on_nonblocking_listener() {
+    let listener = UnixSeqpacketListener::bind_temp().unwrap();
+    listener.set_nonblocking(true).unwrap();
+    let _client = UnixSeqpacketConn::connect_unix_addr(listener.addr()).unwrap();
+    let timeout = Duration::from_millis(100);
+    let (a, b) = std::thread::scope(|scope| {
+        let a = scope.spawn(|| listener.accept_timeout(timeout) );
+        let b = scope.spawn(|| listener.accept_timeout(timeout) );
+        (a.join().unwrap(), b.join().unwrap())
+    });
+    match (a, b) {
+        (Ok(_), Err(e)) | (Err(e), Ok(_)) => assert_eq!(e.kind(), TimedOut),
+        (a, b) => panic!("expected one connection and one timeout, got {:?} and {:?}", a, b),
+    }
+}
+
+#[test]
+fn accept_unless_shutdown() {
+    use uds::ShutdownHandle;
+
+    let listener = UnixSeqpacketListener::bind_temp().unwrap();
+    let handle = ShutdownHandle::new().unwrap();
+    let _client = UnixSeqpacketConn::connect_unix_addr(listener.addr()).unwrap();
+    assert!(listener.accept_unless_shutdown(&handle).unwrap().is_some());
+
+    let for_thread = handle.clone();
+    let server = std::thread::spawn(move || {
+        let mut accepted = 0;
+        while listener.accept_unless_shutdown(&for_thread).unwrap().is_some() {
+            accepted += 1;
+        }
+        accepted
+    });
+    std::thread::sleep(Duration::from_millis(20));
+    assert!(!handle.is_shutdown());
+    handle.shutdown().unwrap();
+    handle.shutdown().expect("shutting down twice is fine");
+    assert!(handle.is_shutdown());
+    assert_eq!(server.join().unwrap(), 0);
+}
+
+#[test]
+fn blocking_nonblocking_conversion() {
+    let (listener, temp) = UnixSeqpacketListener::bind_temp().unwrap().into_parts();
+    let listener = listener.into_nonblocking().unwrap();
+    assert_eq!(listener.accept_unix_addr().unwrap_err().kind(), WouldBlock);
+    let client = UnixSeqpacketConn::connect_unix_addr(temp.addr()).unwrap();
+    let (accepted, _) = listener.accept_unix_addr().unwrap();
+    assert_eq!(accepted.recv(&mut[0; 10]).unwrap_err().kind(), WouldBlock);
+
+    let listener = listener.into_blocking().unwrap();
+    // only Linux applies timeouts to accept()
+    #[cfg(any(target_os="linux", target_os="android"))] {
+        listener.set_timeout(Some(Duration::from_millis(10))).unwrap();
+        assert_eq!(listener.accept_unix_addr().unwrap_err().kind(), WouldBlock);
+    }
+    assert_eq!(listener.accept_timeout(Duration::from_millis(10)).unwrap_err().kind(), TimedOut);
+
+    let client = client.into_nonblocking().unwrap();
+    assert_eq!(client.recv(&mut[0; 10]).unwrap_err().kind(), WouldBlock);
+    let accepted = accepted.into_blocking().unwrap();
+    client.send(b"handed over").unwrap();
+    assert_eq!(accepted.recv(&mut[0; 20]).unwrap(), 11);
+}
@@ -0,0 +1,55 @@
+#![cfg(feature="serde")]
+
+use std::os::unix::net::UnixStream;
+
+use uds::{UnixSocketAddr, UnixStreamExt};
+
+#[test]
+fn path_addr() {
+    let addr = UnixSocketAddr::from_path("/run/uds/@weird\nname").unwrap();
+    let json = serde_json::to_string(&addr).unwrap();
+    assert_eq!(json, r#""/run/uds/@weird\\nname""#);
+    assert_eq!(serde_json::from_str::<UnixSocketAddr>(&json).unwrap(), addr);
+
+    let relative = UnixSocketAddr::from_path("@relative").unwrap();
+    let json = serde_json::to_string(&relative).unwrap();
+    assert_eq!(json, r#""\\@relative""#);
+    assert_eq!(serde_json::from_str::<UnixSocketAddr>(&json).unwrap(), relative);
+}
+
+#[test]
+fn unnamed_addr() {
+    let unnamed = UnixSocketAddr::new_unspecified();
+    assert_eq!(serde_json::to_string(&unnamed).unwrap(), r#""unnamed""#);
+    assert_eq!(serde_json::from_str::<UnixSocketAddr>(r#""unnamed""#).unwrap(), unnamed);
+    assert_eq!(serde_json::from_str::<UnixSocketAddr>(r#""""#).unwrap(), unnamed);
+}
+
+#[cfg(any(target_os="linux", target_os="android"))]
+#[test]
+fn abstract_addr() {
+    let addr = UnixSocketAddr::from_abstract(b"\0binary\xff").unwrap();
+    let json = serde_json::to_string(&addr).unwrap();
+    assert_eq!(json, r#""@\\0binary\\xff""#);
+    assert_eq!(serde_json::from_str::<UnixSocketAddr>(&json).unwrap(), addr);
+}
+
+#[test]
+fn invalid_addr() {
+    assert!(serde_json::from_str::<UnixSocketAddr>(r#""bad\\escape""#).is_err());
+    assert!(serde_json::from_str::<UnixSocketAddr>("42").is_err());
+}
+
+#[test]
+fn peer_credentials() {
+    let (a, _b) = UnixStream::pair().unwrap();
+    let creds = a.initial_peer_credentials().unwrap();
+    let json = serde_json::to_value(creds).unwrap();
+    assert_eq!(json["euid"], creds.euid());
+    if let Some(pid) = creds.pid() {
+        assert_eq!(json["pid"], pid.get());
+        assert_eq!(json["egid"], creds.egid().unwrap());
+    } else {
+        assert_eq!(json["groups"].as_array().unwrap().len(), creds.groups().len());
+    }
+}
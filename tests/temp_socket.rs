@@ -0,0 +1,89 @@
+use std::os::unix::fs::PermissionsExt;
+use std::os::unix::net::{UnixDatagram, UnixListener, UnixStream};
+
+use uds::{TempBound, TempSocketPath, UnixDatagramExt, UnixListenerExt, UnixSocketAddr, UnixStreamExt};
+
+#[test]
+fn private_directory_is_removed() {
+    let temp = TempSocketPath::new_path().expect("create temporary directory");
+    let dir = temp.directory().expect("new_path() always creates a directory").to_owned();
+    let mode = std::fs::metadata(&dir).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o700);
+    let path = temp.addr().as_pathname().unwrap().to_owned();
+    assert!(path.starts_with(&dir));
+
+    let listener = UnixListener::bind_unix_addr(temp.addr()).unwrap();
+    let _client = UnixStream::connect(&path).unwrap();
+    drop(listener);
+    drop(temp);
+    assert!(!path.exists());
+    assert!(!dir.exists());
+}
+
+#[test]
+fn unbound_directory_is_removed() {
+    let temp = TempSocketPath::new_path().unwrap();
+    let dir = temp.directory().unwrap().to_owned();
+    drop(temp);
+    assert!(!dir.exists());
+}
+
+#[test]
+fn prefers_abstract() {
+    let temp = TempSocketPath::new().unwrap();
+    if UnixSocketAddr::has_abstract_addresses() {
+        assert!(temp.addr().is_abstract());
+        assert_eq!(temp.directory(), None);
+    } else {
+        assert!(temp.addr().is_path());
+    }
+}
+
+#[test]
+fn listener() {
+    let listener = UnixListener::bind_temp().unwrap();
+    assert_eq!(&listener.local_unix_addr().unwrap(), listener.addr());
+    let _client = UnixStream::connect_to_unix_addr(listener.addr()).unwrap();
+    listener.accept().unwrap();
+}
+
+#[test]
+fn datagram() {
+    let a = UnixDatagram::bind_temp().unwrap();
+    let b = UnixDatagram::bind_temp().unwrap();
+    assert_ne!(a.addr(), b.addr());
+    b.send_to_unix_addr(b"temporary", a.addr()).unwrap();
+    let (len, from) = a.recv_from_unix_addr(&mut[0; 20]).unwrap();
+    assert_eq!(len, 9);
+    assert_eq!(&from, b.addr());
+}
+
+#[cfg(not(target_vendor="apple"))]
+#[test]
+fn seqpacket() {
+    use uds::{UnixSeqpacketConn, UnixSeqpacketListener};
+
+    let listener = UnixSeqpacketListener::bind_temp().unwrap();
+    let conn = UnixSeqpacketConn::connect_unix_addr(listener.addr()).unwrap();
+    let (accepted, _) = listener.accept_unix_addr().unwrap();
+    conn.send(b"temporary").unwrap();
+    assert_eq!(accepted.recv(&mut[0; 20]).unwrap(), 9);
+}
+
+#[test]
+fn removed_after_socket() {
+    let temp = TempSocketPath::new_path().unwrap();
+    let dir = temp.directory().unwrap().to_owned();
+    let listener = UnixListener::bind_unix_addr(temp.addr()).unwrap();
+    let bound = TempBound::new(listener, temp);
+    let path = bound.addr().as_pathname().unwrap().to_owned();
+    let _client = UnixStream::connect(&path).unwrap();
+    bound.accept().unwrap();
+
+    let (listener, temp) = bound.into_parts();
+    assert!(path.exists());
+    let bound = TempBound::new(listener, temp);
+    drop(bound);
+    assert!(!path.exists());
+    assert!(!dir.exists());
+}
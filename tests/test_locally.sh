@@ -51,6 +51,8 @@ cargo +nightly check $CAFLAGS -Z minimal-versions --features mio
 # no version of mio-uds compiles with minimal versions
 cargo +nightly check $CAFLAGS -Z minimal-versions --features mio_07
 cargo +nightly check $CAFLAGS -Z minimal-versions --features mio_08
+cargo +nightly check $CAFLAGS -Z minimal-versions --features mio_1
+cargo +nightly check $CAFLAGS -Z minimal-versions --features async-io
 rm Cargo.lock
 echo
 